@@ -1,7 +1,11 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use crate::schemas::states::pool::{
     Pool,
+    POOL_SEED,
+    REWADER_SEED,
 };
 use crate::schemas::states::payroll::{
+    Payroll,
     PAYROLL_SEED,
     PAYROLL_PDA_LEN
 };
@@ -9,19 +13,59 @@ use crate::schemas::states::payroll::{
 use crate::schemas::states::staking_account::{
     STAKING_SEED,
 };
+use crate::schemas::states::staking_payroll::{
+    STAKING_PAYROLL_SEED,
+};
+use crate::schemas::states::token_data::{
+    TOKEN_DATA_SEED,
+};
+use crate::schemas::states::reward_vault::{
+    REWARD_VAULT_SEED,
+};
+use crate::schemas::states::payroll_reward::{
+    PAYROLL_REWARD_SEED,
+};
+use crate::schemas::states::power_rules::{
+    PowerRules,
+    POWER_RULES_PDA_LEN,
+    POWER_RULES_SEED,
+};
+use crate::schemas::states::token_data::{
+    TokenData,
+    TOKEN_DATA_PDA_LEN,
+};
+use crate::schemas::states::pool_collections::{
+    PoolCollections,
+    POOL_COLLECTIONS_SEED,
+};
+use crate::schemas::instructions::pool_deposit::PowerProof;
+use mpl_token_metadata::{
+    ID as MPL_PROGRAM_ID,
+    accounts::Metadata,
+    instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs},
+    types::TokenStandard,
+};
 use solana_program::{
     clock::Clock,
+    entrypoint::ProgramResult,
     sysvar::Sysvar,
     program_error::ProgramError,
     program::{ invoke_signed },
+    program_pack::Pack,
     rent::Rent,
-    pubkey::Pubkey, account_info::AccountInfo,
+    pubkey::Pubkey, account_info::{next_account_info, AccountInfo},
     system_instruction,
     system_program::ID as SYSTEM_PROGRAM_ID,
-    msg,
+    keccak::hashv,
+    pubkey,
 };
 use spl_associated_token_account::{
-    get_associated_token_address
+    get_associated_token_address,
+    get_associated_token_address_with_program_id,
+};
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::{Account as TokenAccount, Mint as TokenMint},
 };
 use std::{
     convert::TryInto
@@ -29,14 +73,37 @@ use std::{
 use crate::error::ContractError::{
     InvalidProgramAccount,
     InvalidAtaAccount,
+    InvalidPowerAuthority,
+    InvalidPoolCreator,
+    InvalidLockTier,
+    InvalidPdaAccount,
+    InvalidPowerProof,
+    InvalidCollection,
+    InvalidAllowlistProof,
+    MissingCollection,
+    InvalidStakingMint,
+    InvalidRewardToken,
+    MathOverflow,
 };
 
 pub const DECIMAL_REWARD: u32 = 6;
+pub const MAX_REWARD_DECIMALS: u32 = 18;
+// lock tier multipliers are in basis points, 10000 is 1.0x
+pub const MULTIPLIER_BASE: u64 = 10_000;
 pub const POOL_ACCOUNT_TYPE: u8 = 100;
 pub const STAKING_ACCOUNT_TYPE: u8 = 101;
 pub const POOL_PAYROLL_ACCOUNT_TYPE: u8 = 102;
 pub const STAKING_PAYROLL_ACCOUNT_TYPE: u8 = 103;
 pub const TOKEN_DATA_ACCOUNT_TYPE: u8 = 104;
+pub const REWARD_VAULT_ACCOUNT_TYPE: u8 = 105;
+pub const PAYROLL_REWARD_ACCOUNT_TYPE: u8 = 106;
+pub const POWER_RULES_ACCOUNT_TYPE: u8 = 107;
+pub const POOL_COLLECTIONS_ACCOUNT_TYPE: u8 = 108;
+// pool_type flags
+pub const POOL_TYPE_NON_CUSTODIAL: u8 = 1;
+pub const POOL_TYPE_CONTINUOUS: u8 = 2;
+// authorization rules program of programmable nfts
+pub const TOKEN_AUTH_RULES_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 pub fn get_current_payroll_index(
     current_at: u64,
@@ -46,13 +113,47 @@ pub fn get_current_payroll_index(
     ((current_at - start_at) / reward_period) + 1
 }
 
+pub fn get_payroll_claimable_after(
+    payroll_index: u64,
+    pool_data: &Pool,
+) -> u64 {
+    pool_data.start_at + payroll_index * pool_data.reward_period
+}
+
+pub fn is_claim_expired(
+    payroll_index: u64,
+    pool_data: &Pool,
+    now: u64,
+) -> bool {
+    pool_data.claim_expiry > 0
+        && now >= get_payroll_claimable_after(payroll_index, pool_data) + pool_data.claim_expiry
+}
+
+pub fn is_pool_ended(pool_data: &Pool, now: u64) -> bool {
+    pool_data.end_at > 0 && now >= pool_data.end_at
+}
+pub fn is_past_pool_end(payroll_index: u64, pool_data: &Pool) -> bool {
+    pool_data.max_payroll_index > 0 && payroll_index > pool_data.max_payroll_index
+}
+// the payroll running at end_at, or ending right at it
+pub fn get_max_payroll_index(pool_data: &Pool) -> u64 {
+    match pool_data.end_at > pool_data.start_at {
+        true => get_current_payroll_index(
+            pool_data.end_at - 1,
+            pool_data.reward_period,
+            pool_data.start_at,
+        ),
+        false => 0,
+    }
+}
+
 pub fn get_or_create_payroll_by_index <'a>(
     payroll_index: u64,
     program_id: &Pubkey,
-    main_account: &'a AccountInfo <'a>,
-    pool_pda_account: &'a AccountInfo<'a>,
-    payroll_pda: &'a AccountInfo<'a>,
-    system_program_account: &'a AccountInfo <'a>,
+    main_account: &AccountInfo<'a>,
+    pool_pda_account: &AccountInfo<'a>,
+    payroll_pda: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
 ) -> Result<(Pubkey, u64), ProgramError> {
     let parsed_index = payroll_index.to_string();
     let payroll_account_seeds: &[&[u8]; 3] = &[
@@ -73,13 +174,13 @@ pub fn get_or_create_payroll_by_index <'a>(
     ];
     let pda_payroll_account_data_len = payroll_pda.data_len();
     let payroll_lamports_required = Rent::get().ok().unwrap().minimum_balance(PAYROLL_PDA_LEN);
-    if pda_payroll_account_data_len <= 0 {
+    if pda_payroll_account_data_len == 0 {
         let create_pda_account_ix = system_instruction::create_account(
-            &main_account.key,
-            &payroll_pda.key,
+            main_account.key,
+            payroll_pda.key,
             payroll_lamports_required,
             PAYROLL_PDA_LEN.try_into().unwrap(),
-            &program_id,
+            program_id,
         );
         
         invoke_signed(
@@ -97,10 +198,10 @@ pub fn get_or_create_payroll_by_index <'a>(
 pub fn get_or_create_next_payroll_by_time <'a>(
     now: u64,
     program_id: &Pubkey,
-    main_account: &'a AccountInfo <'a>,
-    pool_pda_account: &'a AccountInfo<'a>,
-    payroll_pda: &'a AccountInfo<'a>,
-    system_program_account: &'a AccountInfo <'a>,
+    main_account: &AccountInfo<'a>,
+    pool_pda_account: &AccountInfo<'a>,
+    payroll_pda: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
     pool_data: Pool
 ) -> Result<(Pubkey, u64), ProgramError> {
     let next_payroll_index = get_current_payroll_index(
@@ -119,10 +220,10 @@ pub fn get_or_create_next_payroll_by_time <'a>(
 }
 pub fn get_or_create_next_payroll <'a>(
     program_id: &Pubkey,
-    main_account: &'a AccountInfo <'a>,
-    pool_pda_account: &'a AccountInfo<'a>,
-    payroll_pda: &'a AccountInfo<'a>,
-    system_program_account: &'a AccountInfo <'a>,
+    main_account: &AccountInfo<'a>,
+    pool_pda_account: &AccountInfo<'a>,
+    payroll_pda: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
     pool_data: Pool
 ) -> Result<(Pubkey, u64), ProgramError> {
     let clock = Clock::get().ok().unwrap();
@@ -140,10 +241,10 @@ pub fn get_or_create_next_payroll <'a>(
 pub fn get_or_create_current_payroll_by_time <'a>(
     now: u64,
     program_id: &Pubkey,
-    main_account: &'a AccountInfo <'a>,
-    pool_pda_account: &'a AccountInfo<'a>,
-    payroll_pda: &'a AccountInfo<'a>,
-    system_program_account: &'a AccountInfo <'a>,
+    main_account: &AccountInfo<'a>,
+    pool_pda_account: &AccountInfo<'a>,
+    payroll_pda: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
     pool_data: Pool
 ) -> Result<(Pubkey, u64), ProgramError> {
     let next_payroll_index = get_current_payroll_index(
@@ -163,10 +264,10 @@ pub fn get_or_create_current_payroll_by_time <'a>(
 
 pub fn get_or_create_current_payroll <'a>(
     program_id: &Pubkey,
-    main_account: &'a AccountInfo <'a>,
-    pool_pda_account: &'a AccountInfo<'a>,
-    payroll_pda: &'a AccountInfo<'a>,
-    system_program_account: &'a AccountInfo <'a>,
+    main_account: &AccountInfo<'a>,
+    pool_pda_account: &AccountInfo<'a>,
+    payroll_pda: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
     pool_data: Pool
 ) -> Result<(Pubkey, u64), ProgramError> {
     let clock = Clock::get().ok().unwrap();
@@ -183,22 +284,183 @@ pub fn get_or_create_current_payroll <'a>(
 
 
 
+// lock duration and power multiplier of a deposit, tier 0 is no lock
+pub fn get_lock_tier(pool_data: &Pool, lock_tier: u8) -> Result<(u64, u16), ProgramError> {
+    match lock_tier {
+        0 => Ok((0, MULTIPLIER_BASE as u16)),
+        tier => match pool_data.lock_tiers.get(tier as usize - 1) {
+            Some(lock_tier) if lock_tier.duration > 0 => Ok((lock_tier.duration, lock_tier.multiplier)),
+            _ => Err(InvalidLockTier.into()),
+        },
+    }
+}
+
+// decimals the payroll reward rates of a pool are scaled by
+pub fn get_reward_decimals(pool_data: &Pool) -> u32 {
+    match pool_data.reward_decimals {
+        0 => DECIMAL_REWARD,
+        reward_decimals => reward_decimals as u32,
+    }
+}
+// a u64 amount scaled by 10^MAX_REWARD_DECIMALS always fits in u128
 pub fn recalculate_reward_rate(
     total_deposited_power: u64,
     total_reward_amount: u64,
-) -> u64 {
-
+    reward_decimals: u32,
+) -> Result<u128, ProgramError> {
     match total_deposited_power {
-        0 => 0,
-        _ => total_reward_amount * u64::pow(10, DECIMAL_REWARD)/ total_deposited_power,
+        0 => Ok(0),
+        _ => Ok((total_reward_amount as u128)
+            .checked_mul(u128::pow(10, reward_decimals))
+            .ok_or(MathOverflow)?
+            / total_deposited_power as u128),
+    }
+}
+// reward of `deposited_power` in a payroll, rounded down so the claims of a
+// payroll never add up to more than its total reward
+pub fn get_payroll_reward(
+    deposited_power: u64,
+    rate_reward: u128,
+    reward_decimals: u32,
+) -> Result<u64, ProgramError> {
+    let reward = (deposited_power as u128)
+        .checked_mul(rate_reward)
+        .ok_or(MathOverflow)?
+        / u128::pow(10, reward_decimals);
+    reward.try_into().map_err(|_| MathOverflow.into())
+}
+// what a stake can still withdraw from a payroll, never more than the payroll
+// has left
+pub fn get_payroll_reward_due(
+    deposited_power: u64,
+    withdrawn_reward: u64,
+    payroll_data: &Payroll,
+    reward_decimals: u32,
+) -> Result<u64, ProgramError> {
+    let earned = get_payroll_reward(deposited_power, payroll_data.rate_reward, reward_decimals)?;
+    let payroll_left = payroll_data
+        .total_reward_amount
+        .saturating_sub(payroll_data.reward_withdrawn_amount);
+    Ok(std::cmp::min(earned.saturating_sub(withdrawn_reward), payroll_left))
+}
+// sets the power and reward of a payroll and the rate they give, keeping what
+// the rounded down rate cannot pay out as the remainder
+pub fn update_payroll_reward(
+    payroll_data: &mut Payroll,
+    total_deposited_power: u64,
+    total_reward_amount: u64,
+    reward_decimals: u32,
+) -> Result<(), ProgramError> {
+    let rate_reward =
+        recalculate_reward_rate(total_deposited_power, total_reward_amount, reward_decimals)?;
+    let distributed_reward = get_payroll_reward(total_deposited_power, rate_reward, reward_decimals)?;
+    payroll_data.total_deposited_power = total_deposited_power;
+    payroll_data.total_reward_amount = total_reward_amount;
+    payroll_data.rate_reward = rate_reward;
+    payroll_data.reward_remainder = total_reward_amount - distributed_reward;
+    Ok(())
+}
+// deposits only earn from the next payroll, until it starts their power is
+// kept apart from the payroll running now
+pub fn add_pending_power(
+    pool_data: &mut Pool,
+    power: u64,
+    first_payroll_index: u64,
+) -> Result<(), ProgramError> {
+    if pool_data.pending_payroll_index != first_payroll_index {
+        pool_data.pending_power = 0;
+        pool_data.pending_payroll_index = first_payroll_index;
+    }
+    pool_data.pending_power = pool_data
+        .pending_power
+        .checked_add(power)
+        .ok_or(MathOverflow)?;
+    Ok(())
+}
+pub fn remove_pending_power(pool_data: &mut Pool, power: u64, first_payroll_index: u64) {
+    if pool_data.pending_payroll_index == first_payroll_index {
+        pool_data.pending_power = pool_data.pending_power.saturating_sub(power);
+    }
+}
+// power earning from a payroll that has not ended yet
+pub fn get_payroll_power(pool_data: &Pool, payroll_index: u64) -> u64 {
+    match payroll_index < pool_data.pending_payroll_index {
+        true => pool_data
+            .total_deposited_power
+            .saturating_sub(pool_data.pending_power),
+        false => pool_data.total_deposited_power,
+    }
+}
+// adds a reward that reached the rewarder of a payroll, the payroll has to
+// exist already. One that has ended keeps the power it ran with
+pub fn add_payroll_reward(
+    payroll_pda: &AccountInfo,
+    pool_pda_account: &AccountInfo,
+    funder: &Pubkey,
+    pool_data: &Pool,
+    payroll_index: u64,
+    amount: u64,
+    now: u64,
+) -> ProgramResult {
+    let current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+    let new_payroll = current_payroll_data.account_type != POOL_PAYROLL_ACCOUNT_TYPE;
+    let mut total_deposited_power = get_payroll_power(pool_data, payroll_index);
+    let mut payroll_total_reward = amount;
+    let mut reward_withdrawn_amount = 0;
+    let mut claimed_power = 0;
+    if !new_payroll {
+        if now >= get_payroll_claimable_after(payroll_index, pool_data) {
+            total_deposited_power = current_payroll_data.total_deposited_power;
+        }
+        payroll_total_reward = payroll_total_reward
+            .checked_add(current_payroll_data.total_reward_amount)
+            .ok_or(MathOverflow)?;
+        reward_withdrawn_amount = current_payroll_data.reward_withdrawn_amount;
+        claimed_power = current_payroll_data.claimed_power;
+    }
+    let mut payroll_data = Payroll {
+        account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+        total_deposited_power: 0,
+        total_reward_amount: 0,
+        rate_reward: 0,
+        reward_remainder: 0,
+        claimed_power,
+        sealed: false,
+        reward_withdrawn_amount,
+        index: payroll_index,
+        start_at: pool_data.start_at,
+        claimable_after: get_payroll_claimable_after(payroll_index, pool_data),
+        pool_pda_account: *pool_pda_account.key,
+        creator: *funder,
+    };
+    update_payroll_reward(
+        &mut payroll_data,
+        total_deposited_power,
+        payroll_total_reward,
+        get_reward_decimals(pool_data),
+    )?;
+    payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
+pub fn is_payroll_fully_claimed(payroll_data: &Payroll) -> bool {
+    payroll_data.claimed_power >= payroll_data.total_deposited_power
+}
+// counts a stake claiming a payroll for the first time, after its withdrawal
+// is added. Claims round down too, so once every stake has claimed the
+// remainder is whatever the payroll still holds
+pub fn record_payroll_claim(payroll_data: &mut Payroll, deposited_power: u64) {
+    payroll_data.claimed_power = payroll_data.claimed_power.saturating_add(deposited_power);
+    if is_payroll_fully_claimed(payroll_data) {
+        payroll_data.reward_remainder = payroll_data
+            .total_reward_amount
+            .saturating_sub(payroll_data.reward_withdrawn_amount);
     }
 }
 
 pub fn verify_program_account(account: &AccountInfo, program_id: &Pubkey) -> Result<(), ProgramError> {
-    msg!("{:?}, {:?}", account.owner, *program_id);
     match *account.owner == *program_id {
         true => Ok(()),
-        false => return Err(InvalidProgramAccount.into()),
+        false => Err(InvalidProgramAccount.into()),
     }
 }
 pub fn verify_system_account(account: &AccountInfo) -> Result<(), ProgramError> {
@@ -210,7 +472,7 @@ pub fn verify_ata_account(
     mint: &Pubkey,
 ) -> Result<(), ProgramError> {
     let token_ata = get_associated_token_address(
-        &address,
+        address,
         mint,
     );
     if token_ata != *ata {
@@ -218,6 +480,94 @@ pub fn verify_ata_account(
     }
     Ok(())
 }
+// staked nfts are classic spl tokens, another program could fake their transfer
+pub fn verify_token_program(token_program: &AccountInfo) -> Result<(), ProgramError> {
+    match *token_program.key == spl_token::id() {
+        true => Ok(()),
+        false => Err(InvalidProgramAccount.into()),
+    }
+}
+// reward mints are owned by the token program or by token-2022
+pub fn verify_reward_token_program(
+    token_program: &AccountInfo,
+    mint: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let supported = *token_program.key == spl_token::id() || *token_program.key == spl_token_2022::id();
+    if !supported || mint.owner != token_program.key {
+        return Err(InvalidRewardToken.into());
+    }
+    Ok(())
+}
+pub fn verify_reward_ata_account(
+    address: &Pubkey,
+    ata: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<(), ProgramError> {
+    let token_ata = get_associated_token_address_with_program_id(
+        address,
+        mint,
+        token_program,
+    );
+    if token_ata != *ata {
+        return Err(InvalidAtaAccount.into());
+    }
+    Ok(())
+}
+pub fn get_token_amount(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(StateWithExtensions::<TokenAccount>::unpack(&token_account.data.borrow())?.base.amount)
+}
+// transfer_checked through the program of the mint, returns what the
+// destination received once transfer fees are withheld
+pub fn transfer_reward<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<u64, ProgramError> {
+    let decimals = StateWithExtensions::<TokenMint>::unpack(&mint.data.borrow())?.base.decimals;
+    let ix = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    let amount_before = get_token_amount(destination)?;
+    invoke_signed(
+        &ix,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signers_seeds,
+    )?;
+    Ok(get_token_amount(destination)? - amount_before)
+}
+// amount to send so the destination receives `amount` after the mint transfer fee
+pub fn get_pre_fee_amount(mint: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    let mint_data = mint.data.borrow();
+    let mint_state = StateWithExtensions::<TokenMint>::unpack(&mint_data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => {
+            let epoch = Clock::get()?.epoch;
+            let fee = fee_config
+                .calculate_inverse_epoch_fee(epoch, amount)
+                .ok_or(InvalidRewardToken)?;
+            Ok(amount + fee)
+        }
+        Err(_) => Ok(amount),
+    }
+}
 pub fn get_staking_pda(
     pool_pda: &Pubkey,
     address: &Pubkey,
@@ -237,3 +587,412 @@ pub fn get_staking_pda(
 
     Ok((expected_pda_account, bump))
 }
+
+pub fn get_pool_pda(
+    name: &[u8; 16],
+    creator: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[name, POOL_SEED, &creator.to_bytes()],
+        program_id
+    )
+}
+pub fn get_payroll_pda(
+    payroll_index: u64,
+    pool_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYROLL_SEED, payroll_index.to_string().as_bytes(), &pool_pda.to_bytes()],
+        program_id
+    )
+}
+pub fn get_rewarder_pda(
+    payroll_pda: &Pubkey,
+    pool_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REWADER_SEED, &payroll_pda.to_bytes(), &pool_pda.to_bytes()],
+        program_id
+    )
+}
+pub fn get_staking_payroll_pda(
+    payroll_index: u64,
+    pool_pda: &Pubkey,
+    staking_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            STAKING_PAYROLL_SEED,
+            payroll_index.to_string().as_bytes(),
+            &pool_pda.to_bytes(),
+            &staking_pda.to_bytes(),
+        ],
+        program_id
+    )
+}
+pub fn get_token_data_pda(
+    mint: &Pubkey,
+    pool_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TOKEN_DATA_SEED, &mint.to_bytes(), &pool_pda.to_bytes()],
+        program_id
+    )
+}
+pub fn get_reward_vault_pda(
+    pool_pda: &Pubkey,
+    reward_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REWARD_VAULT_SEED, &pool_pda.to_bytes(), &reward_mint.to_bytes()],
+        program_id
+    )
+}
+pub fn get_payroll_reward_pda(
+    payroll_pda: &Pubkey,
+    reward_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYROLL_REWARD_SEED, &payroll_pda.to_bytes(), &reward_mint.to_bytes()],
+        program_id
+    )
+}
+pub fn get_power_rules_pda(
+    pool_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POWER_RULES_SEED, &pool_pda.to_bytes()],
+        program_id
+    )
+}
+pub fn get_pool_collections_pda(
+    pool_pda: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POOL_COLLECTIONS_SEED, &pool_pda.to_bytes()],
+        program_id
+    )
+}
+// claim record of a staking account for a vault mint, the pool reward token
+// uses get_staking_payroll_pda
+pub fn get_mint_staking_payroll_pda(
+    payroll_index: u64,
+    pool_pda: &Pubkey,
+    staking_pda: &Pubkey,
+    reward_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            STAKING_PAYROLL_SEED,
+            payroll_index.to_string().as_bytes(),
+            &pool_pda.to_bytes(),
+            &staking_pda.to_bytes(),
+            &reward_mint.to_bytes(),
+        ],
+        program_id
+    )
+}
+pub fn is_non_custodial_pool(pool_data: &Pool) -> bool {
+    pool_data.pool_type & POOL_TYPE_NON_CUSTODIAL != 0
+}
+pub fn verify_pool_creator(
+    creator: &AccountInfo,
+    pool_data: &Pool,
+) -> Result<(), ProgramError> {
+    if !creator.is_signer || pool_data.creator != *creator.key {
+        return Err(InvalidPoolCreator.into());
+    }
+    Ok(())
+}
+pub fn verify_power_authority(
+    authority: &AccountInfo,
+    pool_data: &Pool,
+) -> Result<(), ProgramError> {
+    if !authority.is_signer || pool_data.power_authority != *authority.key {
+        return Err(InvalidPowerAuthority.into());
+    }
+    Ok(())
+}
+// leaves of the power rules and allowlist merkle trees, pairs of nodes are
+// hashed in sorted order
+pub fn get_power_leaf(mint: &Pubkey, power: u64) -> [u8; 32] {
+    hashv(&[&mint.to_bytes(), &power.to_le_bytes()]).to_bytes()
+}
+pub fn get_allowlist_leaf(mint: &Pubkey) -> [u8; 32] {
+    hashv(&[&mint.to_bytes()]).to_bytes()
+}
+pub fn verify_merkle_proof(
+    merkle_root: &[u8; 32],
+    leaf: [u8; 32],
+    proof: &[[u8; 32]],
+) -> bool {
+    let root = proof.iter().fold(leaf, |node, sibling| match node <= *sibling {
+        true => hashv(&[&node, sibling]).to_bytes(),
+        false => hashv(&[sibling, &node]).to_bytes(),
+    });
+    root == *merkle_root
+}
+// the extra collections of the pool, None until they are set
+pub fn get_pool_collections(
+    program_id: &Pubkey,
+    pool_pda_account: &AccountInfo,
+    pool_collections_pda: &AccountInfo,
+) -> Result<Option<PoolCollections>, ProgramError> {
+    let (expected_pool_collections_pda, _) = get_pool_collections_pda(pool_pda_account.key, program_id);
+    if expected_pool_collections_pda != *pool_collections_pda.key {
+        return Err(InvalidPdaAccount.into());
+    }
+    if pool_collections_pda.data_len() == 0 {
+        return Ok(None);
+    }
+    verify_program_account(pool_collections_pda, program_id)?;
+    Ok(Some(PoolCollections::try_from_slice(&pool_collections_pda.data.borrow())?))
+}
+// default power of a token in one of the pool collections
+pub fn get_collection_power(
+    pool_collections: &Option<PoolCollections>,
+    metadata: &Metadata,
+) -> Option<u64> {
+    match (pool_collections, &metadata.collection) {
+        (Some(pool_collections), Some(collection)) if collection.verified => pool_collections
+            .collections
+            .iter()
+            .find(|collection_power| collection_power.collection == collection.key)
+            .map(|collection_power| collection_power.power),
+        _ => None,
+    }
+}
+// metadata of an nft, the metadata pda of a mint of one token without decimals
+pub fn get_nft_metadata(
+    mint_account: &AccountInfo,
+    metadata_pda: &AccountInfo,
+) -> Result<Metadata, ProgramError> {
+    if *mint_account.owner != spl_token::id() {
+        return Err(InvalidStakingMint.into());
+    }
+    let mint = spl_token::state::Mint::unpack(&mint_account.data.borrow())?;
+    if mint.supply != 1 || mint.decimals != 0 {
+        return Err(InvalidStakingMint.into());
+    }
+    let (expected_metadata_pda, _) = Metadata::find_pda(mint_account.key);
+    if *metadata_pda.owner != MPL_PROGRAM_ID || expected_metadata_pda != *metadata_pda.key {
+        return Err(InvalidPdaAccount.into());
+    }
+    Ok(Metadata::safe_deserialize(&metadata_pda.data.borrow())?)
+}
+// tokens of the pool collections, or outside of them with an allowlist proof
+pub fn verify_depositable(
+    pool_data: &Pool,
+    pool_collections: &Option<PoolCollections>,
+    metadata: &Metadata,
+    allowlist_proof: &Option<Vec<[u8; 32]>>,
+) -> Result<(), ProgramError> {
+    if let Some(collection) = &metadata.collection {
+        if collection.verified && collection.key == pool_data.collection {
+            return Ok(());
+        }
+    }
+    if get_collection_power(pool_collections, metadata).is_some() {
+        return Ok(());
+    }
+    match allowlist_proof {
+        None if metadata.collection.is_none() => Err(MissingCollection.into()),
+        None => Err(InvalidCollection.into()),
+        Some(proof) if pool_data.allowlist_root != [0; 32] && verify_merkle_proof(
+            &pool_data.allowlist_root,
+            get_allowlist_leaf(&metadata.mint),
+            proof,
+        ) => Ok(()),
+        Some(_) => Err(InvalidAllowlistProof.into()),
+    }
+}
+// token data of the mint first, then a merkle proof, then the creator powers,
+// and default_power when none of them applies
+pub fn get_token_power(
+    program_id: &Pubkey,
+    pool_pda_account: &AccountInfo,
+    token_data_pda: &AccountInfo,
+    power_rules_pda: &AccountInfo,
+    metadata: &Metadata,
+    power_proof: &Option<PowerProof>,
+    default_power: u64,
+) -> Result<u64, ProgramError> {
+    if token_data_pda.data_len() == TOKEN_DATA_PDA_LEN {
+        return Ok(TokenData::try_from_slice(&token_data_pda.data.borrow())?.power);
+    }
+    let (expected_power_rules_pda, _) = get_power_rules_pda(pool_pda_account.key, program_id);
+    if expected_power_rules_pda != *power_rules_pda.key {
+        return Err(InvalidPdaAccount.into());
+    }
+    let power_rules = match power_rules_pda.data_len() == POWER_RULES_PDA_LEN {
+        true => {
+            verify_program_account(power_rules_pda, program_id)?;
+            Some(PowerRules::try_from_slice(&power_rules_pda.data.borrow())?)
+        }
+        false => None,
+    };
+    if let Some(power_proof) = power_proof {
+        return match power_rules {
+            Some(power_rules) if power_proof.power > 0 && verify_merkle_proof(
+                &power_rules.merkle_root,
+                get_power_leaf(&metadata.mint, power_proof.power),
+                &power_proof.proof,
+            ) => Ok(power_proof.power),
+            _ => Err(InvalidPowerProof.into()),
+        };
+    }
+    let creator_power = match (&power_rules, &metadata.creators) {
+        (Some(power_rules), Some(creators)) => power_rules
+            .creator_powers
+            .iter()
+            .filter(|creator_power| {
+                creator_power.power > 0 && creators.iter().any(|creator| {
+                    creator.verified && creator.address == creator_power.creator
+                })
+            })
+            .map(|creator_power| creator_power.power)
+            .max(),
+        _ => None,
+    };
+    Ok(creator_power.unwrap_or(default_power))
+}
+pub fn close_program_account(
+    account: &AccountInfo,
+    destination: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let destination_lamports = destination.lamports();
+    **destination.lamports.borrow_mut() = destination_lamports
+        .checked_add(account.lamports())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    **account.lamports.borrow_mut() = 0;
+    account.data.borrow_mut().fill(0);
+    Ok(())
+}
+pub fn is_continuous_pool(pool_data: &Pool) -> bool {
+    pool_data.pool_type & POOL_TYPE_CONTINUOUS != 0
+}
+// brings the continuous pool accumulator up to now, emission while nothing
+// is staked is not distributed
+pub fn accrue_emission(pool_data: &mut Pool, now: u64) -> Result<(), ProgramError> {
+    let from = std::cmp::max(pool_data.last_accrued_at, pool_data.start_at);
+    let until = std::cmp::min(now, pool_data.emission_end_at);
+    if until > from {
+        let emitted = ((until - from) as u128)
+            .checked_mul(pool_data.emission_rate as u128)
+            .ok_or(MathOverflow)?;
+        match pool_data.total_deposited_power {
+            // nobody staked, the creator can sweep it
+            0 => {
+                let emitted: u64 = emitted.try_into().map_err(|_| MathOverflow)?;
+                pool_data.undistributed_emission = pool_data
+                    .undistributed_emission
+                    .checked_add(emitted)
+                    .ok_or(MathOverflow)?;
+            }
+            total_deposited_power => {
+                pool_data.acc_reward_per_power = emitted
+                    .checked_mul(u128::pow(10, DECIMAL_REWARD))
+                    .map(|scaled| scaled / total_deposited_power as u128)
+                    .and_then(|per_power| pool_data.acc_reward_per_power.checked_add(per_power))
+                    .ok_or(MathOverflow)?;
+            }
+        }
+    }
+    pool_data.last_accrued_at = std::cmp::max(from, now);
+    Ok(())
+}
+// reward of `power` accrued since the start of the pool
+pub fn get_emission_reward(power: u64, pool_data: &Pool) -> Result<u64, ProgramError> {
+    let reward = (power as u128)
+        .checked_mul(pool_data.acc_reward_per_power)
+        .ok_or(MathOverflow)?
+        / u128::pow(10, DECIMAL_REWARD);
+    Ok(reward.try_into().map_err(|_| MathOverflow)?)
+}
+pub fn is_programmable(token_standard: &Option<TokenStandard>) -> bool {
+    matches!(
+        token_standard,
+        Some(TokenStandard::ProgrammableNonFungible) | Some(TokenStandard::ProgrammableNonFungibleEdition)
+    )
+}
+// accounts the token metadata program needs to move a programmable nft,
+// besides the edition and the program itself
+pub struct ProgrammableAccounts<'b, 'a> {
+    pub token_record: &'b AccountInfo<'a>,
+    pub destination_token_record: &'b AccountInfo<'a>,
+    pub sysvar_instructions: &'b AccountInfo<'a>,
+    pub associated_token_program: &'b AccountInfo<'a>,
+    pub authorization_rules_program: &'b AccountInfo<'a>,
+    // the token metadata program id when the nft has no rule set
+    pub authorization_rules: &'b AccountInfo<'a>,
+}
+pub fn next_programmable_accounts<'b, 'a, I: Iterator<Item = &'b AccountInfo<'a>>>(
+    accounts_iter: &mut I,
+) -> Result<ProgrammableAccounts<'b, 'a>, ProgramError> {
+    Ok(ProgrammableAccounts {
+        token_record: next_account_info(accounts_iter)?,
+        destination_token_record: next_account_info(accounts_iter)?,
+        sysvar_instructions: next_account_info(accounts_iter)?,
+        associated_token_program: next_account_info(accounts_iter)?,
+        authorization_rules_program: next_account_info(accounts_iter)?,
+        authorization_rules: next_account_info(accounts_iter)?,
+    })
+}
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_programmable_nft<'b, 'a>(
+    token_metadata_program: &'b AccountInfo<'a>,
+    source: &'b AccountInfo<'a>,
+    source_owner: &'b AccountInfo<'a>,
+    destination: &'b AccountInfo<'a>,
+    destination_owner: &'b AccountInfo<'a>,
+    mint: &'b AccountInfo<'a>,
+    metadata: &'b AccountInfo<'a>,
+    edition: &'b AccountInfo<'a>,
+    authority: &'b AccountInfo<'a>,
+    payer: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    token_program: &'b AccountInfo<'a>,
+    programmable_accounts: &ProgrammableAccounts<'b, 'a>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if *token_metadata_program.key != MPL_PROGRAM_ID {
+        return Err(InvalidProgramAccount.into());
+    }
+    let has_rules = *programmable_accounts.authorization_rules.key != MPL_PROGRAM_ID;
+    TransferV1Cpi::new(
+        token_metadata_program,
+        TransferV1CpiAccounts {
+            token: source,
+            token_owner: source_owner,
+            destination_token: destination,
+            destination_owner,
+            mint,
+            metadata,
+            edition: Some(edition),
+            token_record: Some(programmable_accounts.token_record),
+            destination_token_record: Some(programmable_accounts.destination_token_record),
+            authority,
+            payer,
+            system_program,
+            sysvar_instructions: programmable_accounts.sysvar_instructions,
+            spl_token_program: token_program,
+            spl_ata_program: programmable_accounts.associated_token_program,
+            authorization_rules_program: has_rules.then_some(programmable_accounts.authorization_rules_program),
+            authorization_rules: has_rules.then_some(programmable_accounts.authorization_rules),
+        },
+        TransferV1InstructionArgs {
+            amount: 1,
+            authorization_data: None,
+        },
+    ).invoke_signed(signers_seeds)
+}
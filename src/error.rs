@@ -1,4 +1,4 @@
-#![allow(clippy::integer_arithmetic)]
+#![allow(clippy::arithmetic_side_effects)]
 use {
     thiserror::Error,
     solana_program::program_error::ProgramError,
@@ -54,6 +54,72 @@ pub enum ContractError {
   // f
   #[error("Invalid collection")]
   InvalidCollection,
+  // 10
+  #[error("Invalid power authority")]
+  InvalidPowerAuthority,
+  // 11
+  #[error("Pool already started")]
+  PoolAlreadyStarted,
+  // 12
+  #[error("Pool is paused")]
+  PoolPaused,
+  // 13
+  #[error("Staked token is still locked")]
+  StillLocked,
+  // 14
+  #[error("Invalid lock tier")]
+  InvalidLockTier,
+  // 15
+  #[error("Unsupported token standard")]
+  UnsupportedTokenStandard,
+  // 16
+  #[error("Fund not withdrawn yet")]
+  FundNotWithdrawn,
+  // 17
+  #[error("Unsupported pool type")]
+  UnsupportedPoolType,
+  // 18
+  #[error("Invalid power proof")]
+  InvalidPowerProof,
+  // 19
+  #[error("Invalid allowlist proof")]
+  InvalidAllowlistProof,
+  // 1a
+  #[error("Missing collection")]
+  MissingCollection,
+  // 1b
+  #[error("Invalid staking token mint")]
+  InvalidStakingMint,
+  // 1c
+  #[error("No reward to roll over")]
+  NoRolloverReward,
+  // 1d
+  #[error("Claim expired")]
+  ClaimExpired,
+  // 1e
+  #[error("Math overflow")]
+  MathOverflow,
+  // 1f
+  #[error("Invalid reward decimals")]
+  InvalidRewardDecimals,
+  // 20
+  #[error("Payroll not sealed")]
+  PayrollNotSealed,
+  // 21
+  #[error("Payroll already sealed")]
+  PayrollSealed,
+  // 22
+  #[error("Pool ended")]
+  PoolEnded,
+  // 23
+  #[error("Payroll past the pool end")]
+  PayrollPastPoolEnd,
+  // 24
+  #[error("Staking position still open")]
+  StakingPositionOpen,
+  // 25
+  #[error("Reward not claimed yet")]
+  RewardNotClaimed,
   
 }
 
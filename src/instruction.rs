@@ -0,0 +1,1692 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+use mpl_token_metadata::accounts::TokenRecord;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use std::{io, ops::RangeInclusive};
+use crate::common::{
+    get_mint_staking_payroll_pda,
+    get_payroll_pda,
+    get_payroll_reward_pda,
+    get_pool_collections_pda,
+    get_pool_pda,
+    get_power_rules_pda,
+    get_reward_vault_pda,
+    get_rewarder_pda,
+    get_staking_pda,
+    get_staking_payroll_pda,
+    get_token_data_pda,
+    TOKEN_AUTH_RULES_ID,
+};
+use crate::schemas::states::pool_collections::CollectionPower;
+use crate::schemas::instructions::{
+    campaign_funding::CampaignFundingIns,
+    crank_budget_funding::CrankBudgetFundingIns,
+    emission_funding::EmissionFundingIns,
+    payroll_rollover::PayrollRolloverIns,
+    pool_collections::PoolCollectionsIns,
+    pool_deposit::{PoolDepositIns, PowerProof},
+    pool_initialization::PoolInitializationIns,
+    pool_update::{PoolCreatorProposalIns, PoolPauseIns, PoolUpdateIns},
+    power_rules::PowerRulesIns,
+    reward_addition::RewardAddition,
+    reward_redemption::{RewardRangeRedemption, RewardRedemption},
+    token_data::TokenDataUpdate,
+};
+
+/// Instructions supported by the staking program.
+///
+/// The leading byte of the instruction data is the tag listed on each variant,
+/// followed by the Borsh encoding of its arguments. Instructions that create
+/// associated token accounts also need the associated token account program
+/// passed after the listed accounts.
+///
+/// Instructions moving reward tokens take the token program that owns the
+/// reward mint, either SPL Token or Token-2022.
+#[derive(Debug, Clone)]
+pub enum StakingInstruction {
+    /// Tag 1. Creates a pool PDA and its reward token account.
+    ///
+    ///   0. `[writable, signer]` Pool creator, pays for the accounts
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[]` Reward token mint
+    ///   3. `[writable]` Pool reward token account
+    ///   4. `[]` Token program
+    ///   5. `[]` System program
+    InitializePool(PoolInitializationIns),
+    /// Tag 2. Adds reward tokens to a payroll, creating the payroll if needed.
+    ///
+    ///   0. `[writable, signer]` Reward funder
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Rewarder PDA of the payroll
+    ///   3. `[]` Reward token mint
+    ///   4. `[writable]` Funder reward token account
+    ///   5. `[writable]` Rewarder reward token account
+    ///   6. `[writable]` Payroll PDA
+    ///   7. `[]` Token program
+    ///   8. `[]` System program
+    AddReward(RewardAddition),
+    /// Tag 3. Stakes an NFT into the pool, optionally locked in one of the
+    /// pool lock tiers.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[writable]` Pool PDA
+    ///   3. `[]` Staking token mint
+    ///   4. `[writable]` Depositor token account
+    ///   5. `[writable]` Pool token account
+    ///   6. `[]` Token data PDA
+    ///   7. `[]` Power rules PDA
+    ///   8. `[]` Pool collections PDA
+    ///   9. `[writable]` Next payroll PDA
+    ///   10. `[]` Token metadata PDA, writable for programmable NFTs
+    ///   11. `[]` Token program
+    ///   12. `[]` System program
+    ///   13. `[]` Master edition PDA, non-custodial pools or programmable NFTs
+    ///   14. `[]` Token metadata program, non-custodial pools or programmable NFTs
+    ///   15. `[writable]` Depositor token record PDA, programmable NFTs only
+    ///   16. `[writable]` Pool token record PDA, programmable NFTs only
+    ///   17. `[]` Instructions sysvar, programmable NFTs only
+    ///   18. `[]` Associated token account program, programmable NFTs only
+    ///   19. `[]` Authorization rules program, programmable NFTs only
+    ///   20. `[]` Authorization rules, or the token metadata program id if the
+    ///       NFT has none, programmable NFTs only
+    ///
+    /// The token power comes from the token data PDA if it exists, otherwise
+    /// from the power proof against the power rules merkle root, otherwise
+    /// from the power rules of a verified creator, otherwise from the pool
+    /// collections, and is 1 by default.
+    ///
+    /// NFTs outside the verified pool collection or pool collections need a
+    /// proof of their mint against the pool allowlist root.
+    ///
+    /// Non-custodial pools leave the NFT in the depositor token account,
+    /// delegated to the pool PDA and frozen. Programmable NFTs, detected from
+    /// the metadata token standard, are moved with the token metadata
+    /// transfer and cannot be staked in non-custodial pools.
+    Deposit(PoolDepositIns),
+    /// Tag 4. Claims the staker's share of a payroll.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[]` Pool PDA
+    ///   3. `[]` Withdrawn address
+    ///   4. `[writable]` Staking payroll PDA
+    ///   5. `[]` Rewarder PDA of the payroll
+    ///   6. `[]` Reward token mint
+    ///   7. `[writable]` Rewarder reward token account
+    ///   8. `[writable]` Withdrawn address reward token account
+    ///   9. `[writable]` Payroll PDA
+    ///   10. `[]` Token program
+    ///   11. `[]` System program
+    WithdrawReward(RewardRedemption),
+    /// Tag 5. Returns the staked NFT to the withdrawn address.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[writable]` Pool PDA
+    ///   3. `[]` Withdrawn address
+    ///   4. `[]` Staking token mint
+    ///   5. `[writable]` Pool token account, or the depositor token account
+    ///      in non-custodial pools
+    ///   6. `[writable]` Withdrawn address token account
+    ///   7. `[writable]` Current payroll PDA
+    ///   8. `[]` Token program
+    ///   9. `[]` System program
+    ///   10. `[]` Master edition PDA, non-custodial pools or programmable NFTs
+    ///   11. `[]` Token metadata program, non-custodial pools or programmable NFTs
+    ///   12. `[writable]` Token metadata PDA, programmable NFTs only
+    ///   13. `[writable]` Pool token record PDA, programmable NFTs only
+    ///   14. `[writable]` Withdrawn address token record PDA, programmable NFTs only
+    ///   15. `[]` Instructions sysvar, programmable NFTs only
+    ///   16. `[]` Associated token account program, programmable NFTs only
+    ///   17. `[]` Authorization rules program, programmable NFTs only
+    ///   18. `[]` Authorization rules, or the token metadata program id if the
+    ///       NFT has none, programmable NFTs only
+    ///
+    /// Non-custodial pools thaw the depositor token account and revoke the
+    /// pool delegation instead of transferring.
+    WithdrawFund,
+    /// Tag 6. Creates the token data PDA holding the staking power of a mint
+    /// in a pool.
+    ///
+    ///   0. `[writable, signer]` Pool power authority
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Staking token mint
+    ///   3. `[writable]` Token data PDA
+    ///   4. `[]` System program
+    SetTokenData(TokenDataUpdate),
+    /// Tag 7. Rewrites the power of an existing token data PDA.
+    ///
+    ///   0. `[signer]` Pool power authority
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Staking token mint
+    ///   3. `[writable]` Token data PDA
+    UpdateTokenData(TokenDataUpdate),
+    /// Tag 8. Closes a token data PDA and returns its rent to the authority.
+    ///
+    ///   0. `[writable, signer]` Pool power authority
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Staking token mint
+    ///   3. `[writable]` Token data PDA
+    CloseTokenData,
+    /// Tag 9. Updates the pool fields that are safe to change. The schedule
+    /// (reward period, start and pool type) and the reward decimals can only
+    /// change before the pool starts.
+    ///
+    ///   0. `[signer]` Pool creator
+    ///   1. `[writable]` Pool PDA
+    UpdatePool(PoolUpdateIns),
+    /// Tag 10. Proposes a new pool creator, who has to accept it.
+    ///
+    ///   0. `[signer]` Pool creator
+    ///   1. `[writable]` Pool PDA
+    ProposePoolCreator(PoolCreatorProposalIns),
+    /// Tag 11. Accepts a pending pool creator proposal.
+    ///
+    ///   0. `[signer]` Proposed pool creator
+    ///   1. `[writable]` Pool PDA
+    AcceptPoolCreator,
+    /// Tag 12. Pauses or resumes deposits, reward withdrawals and fund
+    /// withdrawals. Pausing deposits also stops reward additions.
+    ///
+    ///   0. `[signer]` Pool creator or guardian
+    ///   1. `[writable]` Pool PDA
+    SetPoolPause(PoolPauseIns),
+    /// Tag 13. Registers an extra reward mint on the pool. The pool reward
+    /// token itself cannot be registered.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Reward token mint
+    ///   3. `[writable]` Reward vault PDA
+    ///   4. `[]` System program
+    AddRewardVault,
+    /// Tag 14. Adds reward tokens of a registered mint to a payroll, creating
+    /// the payroll if needed.
+    ///
+    ///   0. `[writable, signer]` Reward funder
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Reward vault PDA
+    ///   3. `[]` Rewarder PDA of the payroll
+    ///   4. `[]` Reward token mint
+    ///   5. `[writable]` Funder reward token account
+    ///   6. `[writable]` Rewarder reward token account
+    ///   7. `[writable]` Payroll PDA
+    ///   8. `[writable]` Payroll reward PDA
+    ///   9. `[]` Token program
+    ///   10. `[]` System program
+    AddMintReward(RewardAddition),
+    /// Tag 15. Claims the staker's share of a registered mint for a payroll.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[]` Staking PDA
+    ///   2. `[]` Pool PDA
+    ///   3. `[]` Withdrawn address
+    ///   4. `[writable]` Mint staking payroll PDA
+    ///   5. `[]` Rewarder PDA of the payroll
+    ///   6. `[]` Reward token mint
+    ///   7. `[writable]` Rewarder reward token account
+    ///   8. `[writable]` Withdrawn address reward token account
+    ///   9. `[]` Payroll PDA
+    ///   10. `[writable]` Payroll reward PDA
+    ///   11. `[writable]` Reward vault PDA
+    ///   12. `[]` Token program
+    ///   13. `[]` System program
+    WithdrawMintReward(RewardRedemption),
+    /// Tag 16. Closes the staking PDA of a withdrawn NFT and its staking
+    /// payrolls, returning their rent to the depositor. Rewards not claimed
+    /// yet are forfeited, their power is counted as claimed on each sealed
+    /// payroll. The empty pool token account is closed as well.
+    ///
+    /// Payrolls are settled in order up to `index`, a position that earned
+    /// from more payrolls than fit in a transaction is closed over several
+    /// ones. The staking PDA is only closed once the payroll before the
+    /// withdrawal is settled. Registered mints are left with their rewarder,
+    /// see `SweepExpiredMintReward`.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[]` Pool PDA
+    ///   3. `[]` Staking token mint
+    ///   4. `[writable]` Pool token account
+    ///   5. `[]` Token program
+    ///
+    /// Payroll pools follow with two accounts per payroll settled, from the
+    /// first payroll not settled yet to `index`:
+    ///
+    ///   0. `[writable]` Payroll PDA
+    ///   1. `[writable]` Staking payroll PDA
+    ///
+    /// Then the staking payroll PDAs of registered mints, any number.
+    CloseStakingPosition(RewardRedemption),
+    /// Tag 17. Claims the staker's share of every payroll from `start_index`
+    /// to `end_index`, both included. Payrolls nobody funded and payrolls
+    /// already claimed are skipped.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[]` Pool PDA
+    ///   3. `[]` Withdrawn address
+    ///   4. `[]` Reward token mint
+    ///   5. `[writable]` Withdrawn address reward token account
+    ///   6. `[]` Token program
+    ///   7. `[]` System program
+    ///   8. `[]` Associated token account program
+    ///
+    /// Followed by four accounts per payroll index, in order:
+    ///
+    ///   0. `[writable]` Payroll PDA
+    ///   1. `[writable]` Staking payroll PDA
+    ///   2. `[]` Rewarder PDA of the payroll
+    ///   3. `[writable]` Rewarder reward token account
+    ClaimRange(RewardRangeRedemption),
+    /// Tag 18. Stakes several NFTs of a custodial pool at once, all in the
+    /// same lock tier. The pool and the next payroll are updated once.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[writable]` Next payroll PDA
+    ///   3. `[]` Power rules PDA
+    ///   4. `[]` Pool collections PDA
+    ///   5. `[]` Token program
+    ///   6. `[]` System program
+    ///   7. `[]` Associated token account program
+    ///
+    /// Followed by six accounts per NFT, in order:
+    ///
+    ///   0. `[]` Staking token mint
+    ///   1. `[writable]` Depositor token account
+    ///   2. `[writable]` Pool token account
+    ///   3. `[]` Token metadata PDA
+    ///   4. `[]` Token data PDA
+    ///   5. `[writable]` Staking PDA
+    ///
+    /// Non-custodial pools, programmable NFTs and power proofs use `Deposit`.
+    BatchDeposit(PoolDepositIns),
+    /// Tag 19. Returns several staked NFTs of a custodial pool to the same
+    /// withdrawn address. The pool and the current payroll are updated once.
+    ///
+    ///   0. `[writable, signer]` Depositor
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[]` Withdrawn address
+    ///   3. `[writable]` Current payroll PDA
+    ///   4. `[]` Token program
+    ///   5. `[]` System program
+    ///   6. `[]` Associated token account program
+    ///
+    /// Followed by four accounts per NFT, in order:
+    ///
+    ///   0. `[]` Staking token mint
+    ///   1. `[writable]` Pool token account
+    ///   2. `[writable]` Withdrawn address token account
+    ///   3. `[writable]` Staking PDA
+    ///
+    /// Non-custodial pools and programmable NFTs use `WithdrawFund`.
+    BatchWithdrawFund,
+    /// Tag 20. Sets the emission of a continuous pool, in reward tokens per
+    /// second until `end_at`. The creator pays what the new emission needs
+    /// beyond what the running one has not emitted yet.
+    ///
+    ///   0. `[signer]` Pool creator
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[]` Reward token mint
+    ///   3. `[writable]` Creator reward token account
+    ///   4. `[writable]` Pool reward token account
+    ///   5. `[]` Token program
+    FundEmission(EmissionFundingIns),
+    /// Tag 21. Pays out what a stake of a continuous pool has accrued so far.
+    ///
+    ///   0. `[writable, signer]` Payer
+    ///   1. `[writable]` Staking PDA
+    ///   2. `[writable]` Pool PDA
+    ///   3. `[]` Withdrawn address
+    ///   4. `[]` Reward token mint
+    ///   5. `[writable]` Pool reward token account
+    ///   6. `[writable]` Withdrawn address reward token account
+    ///   7. `[]` Token program
+    ///   8. `[]` System program
+    ///   9. `[]` Associated token account program
+    ClaimEmission,
+    /// Tag 22. Sets the power rules of the pool, creating the account on
+    /// first use. Signed by the pool power authority.
+    ///
+    ///   0. `[writable, signer]` Power authority
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Power rules PDA
+    ///   3. `[]` System program
+    SetPowerRules(PowerRulesIns),
+    /// Tag 23. Sets the collections accepted by the pool besides the pool
+    /// collection, each with the default power of its tokens. The account is
+    /// created on first use and resized to the list.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Pool collections PDA
+    ///   3. `[]` System program
+    SetPoolCollections(PoolCollectionsIns),
+    /// Tag 24. Moves the reward of a finished payroll that had no deposited
+    /// power, which nobody can claim, or the rounding remainder of one every
+    /// stake has claimed, into a payroll that is not claimable yet. The
+    /// target payroll is created on first use.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Finished payroll PDA
+    ///   3. `[]` Rewarder PDA of the finished payroll
+    ///   4. `[writable]` Finished rewarder reward token account
+    ///   5. `[writable]` Target payroll PDA
+    ///   6. `[]` Rewarder PDA of the target payroll
+    ///   7. `[writable]` Target rewarder reward token account
+    ///   8. `[]` Reward token mint
+    ///   9. `[]` Token program
+    ///   10. `[]` System program
+    RolloverPayroll(PayrollRolloverIns),
+    /// Tag 25. Sends what is left of an expired payroll to the pool creator,
+    /// see `claim_expiry` on the pool.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Payroll PDA
+    ///   3. `[]` Rewarder PDA of the payroll
+    ///   4. `[]` Reward token mint
+    ///   5. `[writable]` Rewarder reward token account
+    ///   6. `[writable]` Creator reward token account
+    ///   7. `[]` Token program
+    ///   8. `[]` System program
+    SweepExpiredReward(RewardRedemption),
+    /// Tag 26. Seals a payroll that has ended, freezing its power and rate.
+    /// Rewards of a payroll can only be claimed once it is sealed. Anyone
+    /// can send it.
+    ///
+    ///   0. `[signer]` Any account
+    ///   1. `[]` Pool PDA
+    ///   2. `[writable]` Payroll PDA
+    SealPayroll(RewardRedemption),
+    /// Tag 27. Creates the current and next payroll of a pool when missing
+    /// and brings their power up to date with the pool. Anyone can send it,
+    /// a crank that creates a payroll is paid the pool crank tip out of the
+    /// crank budget.
+    ///
+    ///   0. `[writable, signer]` Any account, pays the payroll rent
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[writable]` Current payroll PDA
+    ///   3. `[writable]` Next payroll PDA
+    ///   4. `[]` System program
+    CrankPayroll,
+    /// Tag 28. Adds lamports to the crank budget of a pool.
+    ///
+    ///   0. `[writable, signer]` Any account
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[]` System program
+    FundCrankBudget(CrankBudgetFundingIns),
+    /// Tag 29. Adds rewards to a range of payrolls at once, split evenly
+    /// between them.
+    ///
+    ///   0. `[writable, signer]` Funder
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Reward token mint
+    ///   3. `[writable]` Funder reward token account
+    ///   4. `[]` Token program
+    ///   5. `[]` System program
+    ///   6. `[]` Associated token program
+    ///
+    ///   Then for each payroll of the range:
+    ///   0. `[writable]` Payroll PDA
+    ///   1. `[]` Rewarder PDA of the payroll
+    ///   2. `[writable]` Rewarder reward token account
+    FundCampaign(CampaignFundingIns),
+    /// Tag 30. Sends the emission of a continuous pool that no stake earned
+    /// to the pool creator, see `undistributed_emission` on the pool.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[writable]` Pool PDA
+    ///   2. `[]` Reward token mint
+    ///   3. `[writable]` Pool reward token account
+    ///   4. `[writable]` Creator reward token account
+    ///   5. `[]` Token program
+    ///   6. `[]` System program
+    SweepUndistributedEmission,
+    /// Tag 31. Sends what is left of a registered mint on an expired payroll
+    /// to the pool creator, as `SweepExpiredReward` does for the pool reward
+    /// token.
+    ///
+    ///   0. `[writable, signer]` Pool creator
+    ///   1. `[]` Pool PDA
+    ///   2. `[]` Payroll PDA
+    ///   3. `[writable]` Payroll reward PDA
+    ///   4. `[writable]` Reward vault PDA
+    ///   5. `[]` Rewarder PDA of the payroll
+    ///   6. `[]` Reward token mint
+    ///   7. `[writable]` Rewarder reward token account
+    ///   8. `[writable]` Creator reward token account
+    ///   9. `[]` Token program
+    ///   10. `[]` System program
+    SweepExpiredMintReward(RewardRedemption),
+}
+
+impl BorshSerialize for StakingInstruction {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            StakingInstruction::InitializePool(args) => {
+                1u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::AddReward(args) => {
+                2u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::Deposit(args) => {
+                3u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::WithdrawReward(args) => {
+                4u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::WithdrawFund => 5u8.serialize(writer),
+            StakingInstruction::SetTokenData(args) => {
+                6u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::UpdateTokenData(args) => {
+                7u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::CloseTokenData => 8u8.serialize(writer),
+            StakingInstruction::UpdatePool(args) => {
+                9u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::ProposePoolCreator(args) => {
+                10u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::AcceptPoolCreator => 11u8.serialize(writer),
+            StakingInstruction::SetPoolPause(args) => {
+                12u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::AddRewardVault => 13u8.serialize(writer),
+            StakingInstruction::AddMintReward(args) => {
+                14u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::WithdrawMintReward(args) => {
+                15u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::CloseStakingPosition(args) => {
+                16u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::ClaimRange(args) => {
+                17u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::BatchDeposit(args) => {
+                18u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::BatchWithdrawFund => 19u8.serialize(writer),
+            StakingInstruction::FundEmission(args) => {
+                20u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::ClaimEmission => 21u8.serialize(writer),
+            StakingInstruction::SetPowerRules(args) => {
+                22u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::SetPoolCollections(args) => {
+                23u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::RolloverPayroll(args) => {
+                24u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::SweepExpiredReward(args) => {
+                25u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::SealPayroll(args) => {
+                26u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::CrankPayroll => 27u8.serialize(writer),
+            StakingInstruction::FundCrankBudget(args) => {
+                28u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::FundCampaign(args) => {
+                29u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+            StakingInstruction::SweepUndistributedEmission => 30u8.serialize(writer),
+            StakingInstruction::SweepExpiredMintReward(args) => {
+                31u8.serialize(writer)?;
+                args.serialize(writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for StakingInstruction {
+    fn deserialize_reader<R: io::Read>(buf: &mut R) -> io::Result<Self> {
+        let tag = u8::deserialize_reader(buf)?;
+        match tag {
+            1 => Ok(StakingInstruction::InitializePool(PoolInitializationIns::deserialize_reader(buf)?)),
+            2 => Ok(StakingInstruction::AddReward(RewardAddition::deserialize_reader(buf)?)),
+            3 => Ok(StakingInstruction::Deposit(PoolDepositIns::deserialize_reader(buf)?)),
+            4 => Ok(StakingInstruction::WithdrawReward(RewardRedemption::deserialize_reader(buf)?)),
+            5 => Ok(StakingInstruction::WithdrawFund),
+            6 => Ok(StakingInstruction::SetTokenData(TokenDataUpdate::deserialize_reader(buf)?)),
+            7 => Ok(StakingInstruction::UpdateTokenData(TokenDataUpdate::deserialize_reader(buf)?)),
+            8 => Ok(StakingInstruction::CloseTokenData),
+            9 => Ok(StakingInstruction::UpdatePool(PoolUpdateIns::deserialize_reader(buf)?)),
+            10 => Ok(StakingInstruction::ProposePoolCreator(PoolCreatorProposalIns::deserialize_reader(buf)?)),
+            11 => Ok(StakingInstruction::AcceptPoolCreator),
+            12 => Ok(StakingInstruction::SetPoolPause(PoolPauseIns::deserialize_reader(buf)?)),
+            13 => Ok(StakingInstruction::AddRewardVault),
+            14 => Ok(StakingInstruction::AddMintReward(RewardAddition::deserialize_reader(buf)?)),
+            15 => Ok(StakingInstruction::WithdrawMintReward(RewardRedemption::deserialize_reader(buf)?)),
+            16 => Ok(StakingInstruction::CloseStakingPosition(RewardRedemption::deserialize_reader(buf)?)),
+            17 => Ok(StakingInstruction::ClaimRange(RewardRangeRedemption::deserialize_reader(buf)?)),
+            18 => Ok(StakingInstruction::BatchDeposit(PoolDepositIns::deserialize_reader(buf)?)),
+            19 => Ok(StakingInstruction::BatchWithdrawFund),
+            20 => Ok(StakingInstruction::FundEmission(EmissionFundingIns::deserialize_reader(buf)?)),
+            21 => Ok(StakingInstruction::ClaimEmission),
+            22 => Ok(StakingInstruction::SetPowerRules(PowerRulesIns::deserialize_reader(buf)?)),
+            23 => Ok(StakingInstruction::SetPoolCollections(PoolCollectionsIns::deserialize_reader(buf)?)),
+            24 => Ok(StakingInstruction::RolloverPayroll(PayrollRolloverIns::deserialize_reader(buf)?)),
+            25 => Ok(StakingInstruction::SweepExpiredReward(RewardRedemption::deserialize_reader(buf)?)),
+            26 => Ok(StakingInstruction::SealPayroll(RewardRedemption::deserialize_reader(buf)?)),
+            27 => Ok(StakingInstruction::CrankPayroll),
+            28 => Ok(StakingInstruction::FundCrankBudget(CrankBudgetFundingIns::deserialize_reader(buf)?)),
+            29 => Ok(StakingInstruction::FundCampaign(CampaignFundingIns::deserialize_reader(buf)?)),
+            30 => Ok(StakingInstruction::SweepUndistributedEmission),
+            31 => Ok(StakingInstruction::SweepExpiredMintReward(RewardRedemption::deserialize_reader(buf)?)),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Unknown instruction tag")),
+        }
+    }
+}
+
+impl StakingInstruction {
+    pub fn data(&self) -> Vec<u8> {
+        self.try_to_vec().unwrap()
+    }
+}
+
+pub fn initialize_pool(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    reward_token_mint: &Pubkey,
+    args: PoolInitializationIns,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (pool_pda, _) = get_pool_pda(&args.name, creator, program_id);
+    let reward_ata = get_associated_token_address_with_program_id(&pool_pda, reward_token_mint, reward_token_program);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(pool_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(reward_ata, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::InitializePool(args).data(),
+    }
+}
+
+pub fn add_reward(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    amount: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(funder, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::AddReward(RewardAddition {
+            amount,
+            payroll_index,
+        })
+        .data(),
+    }
+}
+
+/// `payroll_index` is the first payroll the deposit earns from, i.e. the
+/// current payroll index plus one. `lock_tier` is 0 for no lock.
+pub fn deposit(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    payroll_index: u64,
+    lock_tier: u8,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let (token_data_pda, _) = get_token_data_pda(staking_token_mint, pool_pda, program_id);
+    let (power_rules_pda, _) = get_power_rules_pda(pool_pda, program_id);
+    let (pool_collections_pda, _) = get_pool_collections_pda(pool_pda, program_id);
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (metadata_pda, _) = mpl_token_metadata::accounts::Metadata::find_pda(staking_token_mint);
+    let (edition_pda, _) = mpl_token_metadata::accounts::MasterEdition::find_pda(staking_token_mint);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new(staking_pda, false),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(get_associated_token_address(depositor, staking_token_mint), false),
+            AccountMeta::new(get_associated_token_address(pool_pda, staking_token_mint), false),
+            AccountMeta::new_readonly(token_data_pda, false),
+            AccountMeta::new_readonly(power_rules_pda, false),
+            AccountMeta::new_readonly(pool_collections_pda, false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(metadata_pda, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(edition_pda, false),
+            AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::Deposit(PoolDepositIns {
+            lock_tier,
+            power_proof: None,
+            allowlist_proof: None,
+        })
+        .data(),
+    }
+}
+
+/// `deposit` of a token whose power is in the pool power rules merkle tree,
+/// see `get_power_leaf`.
+pub fn deposit_with_power_proof(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    payroll_index: u64,
+    lock_tier: u8,
+    power_proof: PowerProof,
+) -> Instruction {
+    let mut instruction = deposit(program_id, depositor, pool_pda, staking_token_mint, payroll_index, lock_tier);
+    instruction.data = StakingInstruction::Deposit(PoolDepositIns {
+        lock_tier,
+        power_proof: Some(power_proof),
+        allowlist_proof: None,
+    })
+    .data();
+    instruction
+}
+
+/// `deposit` of a token outside the pool collection, proven to be in the pool
+/// allowlist, see `get_allowlist_leaf`.
+pub fn deposit_with_allowlist_proof(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    payroll_index: u64,
+    lock_tier: u8,
+    allowlist_proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut instruction = deposit(program_id, depositor, pool_pda, staking_token_mint, payroll_index, lock_tier);
+    instruction.data = StakingInstruction::Deposit(PoolDepositIns {
+        lock_tier,
+        power_proof: None,
+        allowlist_proof: Some(allowlist_proof),
+    })
+    .data();
+    instruction
+}
+
+/// `deposit` of several NFTs in one instruction, see `BatchDeposit`.
+pub fn batch_deposit(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mints: &[Pubkey],
+    payroll_index: u64,
+    lock_tier: u8,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (power_rules_pda, _) = get_power_rules_pda(pool_pda, program_id);
+    let (pool_collections_pda, _) = get_pool_collections_pda(pool_pda, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(*pool_pda, false),
+        AccountMeta::new(payroll_pda, false),
+        AccountMeta::new_readonly(power_rules_pda, false),
+        AccountMeta::new_readonly(pool_collections_pda, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for staking_token_mint in staking_token_mints {
+        let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+        let (token_data_pda, _) = get_token_data_pda(staking_token_mint, pool_pda, program_id);
+        let (metadata_pda, _) = mpl_token_metadata::accounts::Metadata::find_pda(staking_token_mint);
+        accounts.extend([
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(get_associated_token_address(depositor, staking_token_mint), false),
+            AccountMeta::new(get_associated_token_address(pool_pda, staking_token_mint), false),
+            AccountMeta::new_readonly(metadata_pda, false),
+            AccountMeta::new_readonly(token_data_pda, false),
+            AccountMeta::new(staking_pda, false),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: StakingInstruction::BatchDeposit(PoolDepositIns {
+            lock_tier,
+            power_proof: None,
+            allowlist_proof: None,
+        })
+        .data(),
+    }
+}
+
+/// `deposit` for programmable NFTs. `authorization_rules` is the rule set of
+/// the NFT, if it has one.
+pub fn deposit_programmable(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    payroll_index: u64,
+    lock_tier: u8,
+    authorization_rules: Option<&Pubkey>,
+) -> Instruction {
+    let mut instruction = deposit(program_id, depositor, pool_pda, staking_token_mint, payroll_index, lock_tier);
+    instruction.accounts[10].is_writable = true;
+    // the associated token account program moves into the programmable accounts
+    instruction.accounts.pop();
+    instruction.accounts.extend(programmable_account_metas(
+        staking_token_mint,
+        &get_associated_token_address(depositor, staking_token_mint),
+        &get_associated_token_address(pool_pda, staking_token_mint),
+        authorization_rules,
+    ));
+    instruction
+}
+
+fn programmable_account_metas(
+    mint: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    authorization_rules: Option<&Pubkey>,
+) -> Vec<AccountMeta> {
+    let (token_record, _) = TokenRecord::find_pda(mint, source);
+    let (destination_token_record, _) = TokenRecord::find_pda(mint, destination);
+    let (authorization_rules_program, authorization_rules) = match authorization_rules {
+        Some(authorization_rules) => (TOKEN_AUTH_RULES_ID, *authorization_rules),
+        None => (mpl_token_metadata::ID, mpl_token_metadata::ID),
+    };
+    vec![
+        AccountMeta::new(token_record, false),
+        AccountMeta::new(destination_token_record, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(authorization_rules_program, false),
+        AccountMeta::new_readonly(authorization_rules, false),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_reward(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let (staking_payroll_pda, _) = get_staking_payroll_pda(payroll_index, pool_pda, &staking_pda, program_id);
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new(staking_pda, false),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*withdrawn_address, false),
+            AccountMeta::new(staking_payroll_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(withdrawn_address, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::WithdrawReward(RewardRedemption {
+            index: payroll_index,
+        })
+        .data(),
+    }
+}
+
+/// `payroll_indexes` is the range of payrolls to claim, e.g. `2..=10`.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_range(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_indexes: RangeInclusive<u64>,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let mut accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(staking_pda, false),
+        AccountMeta::new_readonly(*pool_pda, false),
+        AccountMeta::new_readonly(*withdrawn_address, false),
+        AccountMeta::new_readonly(*reward_token_mint, false),
+        AccountMeta::new(get_associated_token_address_with_program_id(withdrawn_address, reward_token_mint, reward_token_program), false),
+        AccountMeta::new_readonly(*reward_token_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for index in payroll_indexes.clone() {
+        let (payroll_pda, _) = get_payroll_pda(index, pool_pda, program_id);
+        let (staking_payroll_pda, _) = get_staking_payroll_pda(index, pool_pda, &staking_pda, program_id);
+        let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+        accounts.extend([
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new(staking_payroll_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: StakingInstruction::ClaimRange(RewardRangeRedemption {
+            start_index: *payroll_indexes.start(),
+            end_index: *payroll_indexes.end(),
+        })
+        .data(),
+    }
+}
+
+/// `payroll_index` is the current payroll index.
+pub fn withdraw_fund(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    payroll_index: u64,
+) -> Instruction {
+    withdraw_fund_from(
+        program_id,
+        depositor,
+        pool_pda,
+        staking_token_mint,
+        withdrawn_address,
+        &get_associated_token_address(pool_pda, staking_token_mint),
+        payroll_index,
+    )
+}
+
+/// `withdraw_fund` of several NFTs in one instruction, see
+/// `BatchWithdrawFund`.
+pub fn batch_withdraw_fund(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mints: &[Pubkey],
+    withdrawn_address: &Pubkey,
+    payroll_index: u64,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let mut accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(*pool_pda, false),
+        AccountMeta::new_readonly(*withdrawn_address, false),
+        AccountMeta::new(payroll_pda, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for staking_token_mint in staking_token_mints {
+        let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+        accounts.extend([
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(get_associated_token_address(pool_pda, staking_token_mint), false),
+            AccountMeta::new(get_associated_token_address(withdrawn_address, staking_token_mint), false),
+            AccountMeta::new(staking_pda, false),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: StakingInstruction::BatchWithdrawFund.data(),
+    }
+}
+
+/// `withdraw_fund` for non-custodial pools, where the NFT is frozen in the
+/// depositor token account.
+pub fn withdraw_delegated_fund(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    payroll_index: u64,
+) -> Instruction {
+    withdraw_fund_from(
+        program_id,
+        depositor,
+        pool_pda,
+        staking_token_mint,
+        withdrawn_address,
+        &get_associated_token_address(depositor, staking_token_mint),
+        payroll_index,
+    )
+}
+
+/// `withdraw_fund` for programmable NFTs. `authorization_rules` is the rule
+/// set of the NFT, if it has one.
+pub fn withdraw_programmable_fund(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    payroll_index: u64,
+    authorization_rules: Option<&Pubkey>,
+) -> Instruction {
+    let mut instruction = withdraw_fund(
+        program_id,
+        depositor,
+        pool_pda,
+        staking_token_mint,
+        withdrawn_address,
+        payroll_index,
+    );
+    let (metadata_pda, _) = mpl_token_metadata::accounts::Metadata::find_pda(staking_token_mint);
+    instruction.accounts.pop();
+    instruction.accounts.push(AccountMeta::new(metadata_pda, false));
+    instruction.accounts.extend(programmable_account_metas(
+        staking_token_mint,
+        &get_associated_token_address(pool_pda, staking_token_mint),
+        &get_associated_token_address(withdrawn_address, staking_token_mint),
+        authorization_rules,
+    ));
+    instruction
+}
+
+fn withdraw_fund_from(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    staking_token_account: &Pubkey,
+    payroll_index: u64,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (edition_pda, _) = mpl_token_metadata::accounts::MasterEdition::find_pda(staking_token_mint);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new(staking_pda, false),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(*withdrawn_address, false),
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(*staking_token_account, false),
+            AccountMeta::new(get_associated_token_address(withdrawn_address, staking_token_mint), false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(edition_pda, false),
+            AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::WithdrawFund.data(),
+    }
+}
+
+pub fn set_token_data(
+    program_id: &Pubkey,
+    power_authority: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    token_power: u64,
+) -> Instruction {
+    let (token_data_pda, _) = get_token_data_pda(staking_token_mint, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*power_authority, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(token_data_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::SetTokenData(TokenDataUpdate { token_power }).data(),
+    }
+}
+
+pub fn update_token_data(
+    program_id: &Pubkey,
+    power_authority: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    token_power: u64,
+) -> Instruction {
+    let (token_data_pda, _) = get_token_data_pda(staking_token_mint, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*power_authority, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(token_data_pda, false),
+        ],
+        data: StakingInstruction::UpdateTokenData(TokenDataUpdate { token_power }).data(),
+    }
+}
+
+pub fn close_token_data(
+    program_id: &Pubkey,
+    power_authority: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+) -> Instruction {
+    let (token_data_pda, _) = get_token_data_pda(staking_token_mint, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*power_authority, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*staking_token_mint, false),
+            AccountMeta::new(token_data_pda, false),
+        ],
+        data: StakingInstruction::CloseTokenData.data(),
+    }
+}
+
+pub fn update_pool(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    args: PoolUpdateIns,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*pool_pda, false),
+        ],
+        data: StakingInstruction::UpdatePool(args).data(),
+    }
+}
+
+pub fn propose_pool_creator(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    new_creator: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*pool_pda, false),
+        ],
+        data: StakingInstruction::ProposePoolCreator(PoolCreatorProposalIns {
+            new_creator: *new_creator,
+        })
+        .data(),
+    }
+}
+
+pub fn accept_pool_creator(
+    program_id: &Pubkey,
+    new_creator: &Pubkey,
+    pool_pda: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*new_creator, true),
+            AccountMeta::new(*pool_pda, false),
+        ],
+        data: StakingInstruction::AcceptPoolCreator.data(),
+    }
+}
+
+pub fn set_pool_pause(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_pda: &Pubkey,
+    args: PoolPauseIns,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*pool_pda, false),
+        ],
+        data: StakingInstruction::SetPoolPause(args).data(),
+    }
+}
+
+pub fn add_reward_vault(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+) -> Instruction {
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, reward_token_mint, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(reward_vault_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::AddRewardVault.data(),
+    }
+}
+
+pub fn add_mint_reward(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    amount: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, reward_token_mint, program_id);
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    let (payroll_reward_pda, _) = get_payroll_reward_pda(&payroll_pda, reward_token_mint, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(reward_vault_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(funder, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new(payroll_reward_pda, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::AddMintReward(RewardAddition {
+            amount,
+            payroll_index,
+        })
+        .data(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_mint_reward(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let (staking_payroll_pda, _) =
+        get_mint_staking_payroll_pda(payroll_index, pool_pda, &staking_pda, reward_token_mint, program_id);
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    let (payroll_reward_pda, _) = get_payroll_reward_pda(&payroll_pda, reward_token_mint, program_id);
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, reward_token_mint, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new_readonly(staking_pda, false),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(*withdrawn_address, false),
+            AccountMeta::new(staking_payroll_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(withdrawn_address, reward_token_mint, reward_token_program), false),
+            AccountMeta::new_readonly(payroll_pda, false),
+            AccountMeta::new(payroll_reward_pda, false),
+            AccountMeta::new(reward_vault_pda, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::WithdrawMintReward(RewardRedemption {
+            index: payroll_index,
+        })
+        .data(),
+    }
+}
+
+/// `staking_payrolls` are the staking payroll PDAs of the position to close,
+/// see `get_staking_payroll_pda` and `get_mint_staking_payroll_pda`.
+/// `payroll_indexes` are the payrolls settled by this transaction, from the
+/// first one not settled yet, empty for continuous pools.
+pub fn close_staking_position(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    payroll_indexes: RangeInclusive<u64>,
+    staking_payrolls: &[Pubkey],
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    let mut accounts = vec![
+        AccountMeta::new(*depositor, true),
+        AccountMeta::new(staking_pda, false),
+        AccountMeta::new_readonly(*pool_pda, false),
+        AccountMeta::new_readonly(*staking_token_mint, false),
+        AccountMeta::new(get_associated_token_address(pool_pda, staking_token_mint), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    let end_index = *payroll_indexes.end();
+    for index in payroll_indexes {
+        let (payroll_pda, _) = get_payroll_pda(index, pool_pda, program_id);
+        let (staking_payroll_pda, _) = get_staking_payroll_pda(index, pool_pda, &staking_pda, program_id);
+        accounts.extend([
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new(staking_payroll_pda, false),
+        ]);
+    }
+    accounts.extend(staking_payrolls.iter().map(|staking_payroll| AccountMeta::new(*staking_payroll, false)));
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: StakingInstruction::CloseStakingPosition(RewardRedemption { index: end_index }).data(),
+    }
+}
+
+pub fn fund_emission(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    emission_rate: u64,
+    end_at: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(creator, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(pool_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+        ],
+        data: StakingInstruction::FundEmission(EmissionFundingIns {
+            emission_rate,
+            end_at,
+        })
+        .data(),
+    }
+}
+
+pub fn claim_emission(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    pool_pda: &Pubkey,
+    staking_token_mint: &Pubkey,
+    withdrawn_address: &Pubkey,
+    reward_token_mint: &Pubkey,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (staking_pda, _) = get_staking_pda(pool_pda, depositor, staking_token_mint, program_id).unwrap();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*depositor, true),
+            AccountMeta::new(staking_pda, false),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(*withdrawn_address, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(pool_pda, reward_token_mint, reward_token_program), false),
+            AccountMeta::new(get_associated_token_address_with_program_id(withdrawn_address, reward_token_mint, reward_token_program), false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::ClaimEmission.data(),
+    }
+}
+
+pub fn set_power_rules(
+    program_id: &Pubkey,
+    power_authority: &Pubkey,
+    pool_pda: &Pubkey,
+    power_rules: PowerRulesIns,
+) -> Instruction {
+    let (power_rules_pda, _) = get_power_rules_pda(pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*power_authority, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(power_rules_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::SetPowerRules(power_rules).data(),
+    }
+}
+
+pub fn set_pool_collections(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    collections: Vec<CollectionPower>,
+) -> Instruction {
+    let (pool_collections_pda, _) = get_pool_collections_pda(pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(pool_collections_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::SetPoolCollections(PoolCollectionsIns { collections }).data(),
+    }
+}
+
+pub fn rollover_payroll(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    from_index: u64,
+    to_index: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (from_payroll_pda, _) = get_payroll_pda(from_index, pool_pda, program_id);
+    let (from_rewarder_pda, _) = get_rewarder_pda(&from_payroll_pda, pool_pda, program_id);
+    let (to_payroll_pda, _) = get_payroll_pda(to_index, pool_pda, program_id);
+    let (to_rewarder_pda, _) = get_rewarder_pda(&to_payroll_pda, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(from_payroll_pda, false),
+            AccountMeta::new_readonly(from_rewarder_pda, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&from_rewarder_pda, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new(to_payroll_pda, false),
+            AccountMeta::new_readonly(to_rewarder_pda, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&to_rewarder_pda, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::RolloverPayroll(PayrollRolloverIns { from_index, to_index }).data(),
+    }
+}
+
+pub fn sweep_expired_reward(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(creator, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::SweepExpiredReward(RewardRedemption {
+            index: payroll_index,
+        })
+        .data(),
+    }
+}
+
+pub fn seal_payroll(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    pool_pda: &Pubkey,
+    payroll_index: u64,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*account, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new(payroll_pda, false),
+        ],
+        data: StakingInstruction::SealPayroll(RewardRedemption {
+            index: payroll_index,
+        })
+        .data(),
+    }
+}
+
+pub fn crank_payroll(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    pool_pda: &Pubkey,
+    current_payroll_index: u64,
+) -> Instruction {
+    let (current_payroll_pda, _) = get_payroll_pda(current_payroll_index, pool_pda, program_id);
+    let (next_payroll_pda, _) = get_payroll_pda(current_payroll_index + 1, pool_pda, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*account, true),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new(current_payroll_pda, false),
+            AccountMeta::new(next_payroll_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::CrankPayroll.data(),
+    }
+}
+
+pub fn fund_crank_budget(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    pool_pda: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*account, true),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: StakingInstruction::FundCrankBudget(CrankBudgetFundingIns { amount }).data(),
+    }
+}
+
+/// `payroll_indexes` is the range of payrolls the campaign funds, e.g. `2..=10`.
+pub fn fund_campaign(
+    program_id: &Pubkey,
+    funder: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_indexes: RangeInclusive<u64>,
+    amount: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*funder, true),
+        AccountMeta::new_readonly(*pool_pda, false),
+        AccountMeta::new_readonly(*reward_token_mint, false),
+        AccountMeta::new(get_associated_token_address_with_program_id(funder, reward_token_mint, reward_token_program), false),
+        AccountMeta::new_readonly(*reward_token_program, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for index in payroll_indexes.clone() {
+        let (payroll_pda, _) = get_payroll_pda(index, pool_pda, program_id);
+        let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+        accounts.extend([
+            AccountMeta::new(payroll_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new(get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program), false),
+        ]);
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: StakingInstruction::FundCampaign(CampaignFundingIns {
+            amount,
+            start_index: *payroll_indexes.start(),
+            end_index: *payroll_indexes.end(),
+        })
+        .data(),
+    }
+}
+
+pub fn sweep_undistributed_emission(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(*pool_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(pool_pda, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(creator, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::SweepUndistributedEmission.data(),
+    }
+}
+
+pub fn sweep_expired_mint_reward(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    pool_pda: &Pubkey,
+    reward_token_mint: &Pubkey,
+    payroll_index: u64,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    let (payroll_reward_pda, _) = get_payroll_reward_pda(&payroll_pda, reward_token_mint, program_id);
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, reward_token_mint, program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*pool_pda, false),
+            AccountMeta::new_readonly(payroll_pda, false),
+            AccountMeta::new(payroll_reward_pda, false),
+            AccountMeta::new(reward_vault_pda, false),
+            AccountMeta::new_readonly(rewarder_pda, false),
+            AccountMeta::new_readonly(*reward_token_mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&rewarder_pda, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(creator, reward_token_mint, reward_token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*reward_token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: StakingInstruction::SweepExpiredMintReward(RewardRedemption {
+            index: payroll_index,
+        })
+        .data(),
+    }
+}
@@ -3,4 +3,29 @@ pub mod pool_deposit;
 pub mod rewarder_addition;
 pub mod reward_withdrawal;
 pub mod fund_withdrawal;
-pub mod token_data;
\ No newline at end of file
+pub mod token_data;
+pub mod token_data_update;
+pub mod token_data_closure;
+pub mod pool_update;
+pub mod pool_creator_proposal;
+pub mod pool_creator_acceptance;
+pub mod pool_pause;
+pub mod reward_vault;
+pub mod mint_reward_addition;
+pub mod mint_reward_withdrawal;
+pub mod staking_position_closure;
+pub mod reward_range_withdrawal;
+pub mod pool_batch_deposit;
+pub mod fund_batch_withdrawal;
+pub mod emission_funding;
+pub mod emission_withdrawal;
+pub mod power_rules_update;
+pub mod pool_collections_update;
+pub mod payroll_rollover;
+pub mod expired_reward_sweep;
+pub mod payroll_seal;
+pub mod payroll_crank;
+pub mod crank_budget_funding;
+pub mod campaign_funding;
+pub mod undistributed_emission_sweep;
+pub mod expired_mint_reward_sweep;
\ No newline at end of file
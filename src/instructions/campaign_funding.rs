@@ -0,0 +1,148 @@
+use crate::common::{
+    add_payroll_reward, get_or_create_payroll_by_index, get_payroll_pda, get_rewarder_pda,
+    is_continuous_pool, is_past_pool_end, transfer_reward, verify_program_account,
+    verify_reward_ata_account, verify_reward_token_program, verify_system_account,
+};
+use crate::error::ContractError;
+use crate::schemas::instructions::campaign_funding::CampaignFundingIns;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::Pool;
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: CampaignFundingIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let reward_token_mint_account = next_account_info(accounts_iter)?;
+    let reward_token_source_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    // continuous pools are funded with FundEmission
+    if is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint_account.key {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
+    verify_reward_ata_account(
+        account.key,
+        reward_token_source_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    let CampaignFundingIns { amount, start_index, end_index } = inst_data;
+    if end_index < start_index {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    if is_past_pool_end(end_index, &pool_data) {
+        return Err(ContractError::PayrollPastPoolEnd.into());
+    }
+    let payroll_count = end_index - start_index + 1;
+    // every payroll of the campaign gets something
+    if amount < payroll_count {
+        return Err(ContractError::InvalidDepositAmount.into());
+    }
+    let payroll_amount = amount / payroll_count;
+    let mut undivided_amount = amount % payroll_count;
+    let now = Clock::get()?.unix_timestamp as u64;
+    for index in start_index..=end_index {
+        let payroll_pda = next_account_info(accounts_iter)?;
+        let rewarder_pda = next_account_info(accounts_iter)?;
+        let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+        let (expected_payroll, _bump) = get_payroll_pda(index, pool_pda_account.key, program_id);
+        if expected_payroll != *payroll_pda.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        if payroll_pda.data_len() == 0 {
+            get_or_create_payroll_by_index(
+                index,
+                program_id,
+                account,
+                pool_pda_account,
+                payroll_pda,
+                system_program_account,
+            )?;
+        } else {
+            verify_program_account(payroll_pda, program_id)?;
+            if Payroll::try_from_slice(&payroll_pda.data.borrow())?.sealed {
+                return Err(ContractError::PayrollSealed.into());
+            }
+        }
+        let (expected_rewarder, _bump) =
+            get_rewarder_pda(payroll_pda.key, pool_pda_account.key, program_id);
+        if expected_rewarder != *rewarder_pda.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        verify_reward_ata_account(
+            rewarder_pda.key,
+            reward_token_dest_associated_account.key,
+            reward_token_mint_account.key,
+            token_program_account.key,
+        )?;
+        if reward_token_dest_associated_account.data_len() == 0 {
+            let create_token_account_ix = spl_instruction::create_associated_token_account(
+                account.key,
+                rewarder_pda.key,
+                reward_token_mint_account.key,
+                token_program_account.key,
+            );
+            invoke(
+                &create_token_account_ix,
+                &[
+                    account.clone(),
+                    reward_token_dest_associated_account.clone(),
+                    rewarder_pda.clone(),
+                    reward_token_mint_account.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                ],
+            )?;
+        }
+        let mut transfer_amount = payroll_amount;
+        if undivided_amount > 0 {
+            transfer_amount += 1;
+            undivided_amount -= 1;
+        }
+        // transfer fees are withheld, only what the rewarder received is paid out
+        let received_amount = transfer_reward(
+            token_program_account,
+            reward_token_source_associated_account,
+            reward_token_mint_account,
+            reward_token_dest_associated_account,
+            account,
+            transfer_amount,
+            &[],
+        )?;
+        add_payroll_reward(
+            payroll_pda,
+            pool_pda_account,
+            account.key,
+            &pool_data,
+            index,
+            received_amount,
+            now,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::common::{verify_program_account, verify_system_account};
+use crate::error::ContractError;
+use crate::schemas::instructions::crank_budget_funding::CrankBudgetFundingIns;
+use crate::schemas::states::pool::Pool;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: CrankBudgetFundingIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if inst_data.amount == 0 {
+        return Err(ContractError::InvalidDepositAmount.into());
+    }
+    // anyone can fund the budget, the lamports stay in the pool account
+    invoke(
+        &system_instruction::transfer(account.key, pool_pda_account.key, inst_data.amount),
+        &[
+            account.clone(),
+            pool_pda_account.clone(),
+            system_program_account.clone(),
+        ],
+    )?;
+    pool_data.crank_budget = pool_data
+        .crank_budget
+        .checked_add(inst_data.amount)
+        .ok_or(ContractError::MathOverflow)?;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
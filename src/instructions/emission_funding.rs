@@ -0,0 +1,91 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use crate::common::{
+    accrue_emission, get_pre_fee_amount, is_continuous_pool, transfer_reward, verify_pool_creator,
+    verify_program_account, verify_reward_ata_account, verify_reward_token_program,
+};
+use crate::schemas::instructions::emission_funding::EmissionFundingIns;
+use crate::schemas::states::pool::Pool;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: EmissionFundingIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let reward_token_mint_account = next_account_info(accounts_iter)?;
+    let reward_token_source_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    if pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if !is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint_account.key
+        || pool_data.reward_ata != *reward_token_pool_associated_account.key
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
+    verify_reward_ata_account(
+        account.key,
+        reward_token_source_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    let from = std::cmp::max(now, pool_data.start_at);
+    if inst_data.end_at <= from {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    accrue_emission(&mut pool_data, now)?;
+    // what the running emission has not paid out yet counts toward the new one
+    let unemitted = match pool_data.emission_end_at > from {
+        true => pool_data
+            .emission_rate
+            .checked_mul(pool_data.emission_end_at - from)
+            .ok_or(ContractError::MathOverflow)?,
+        false => 0,
+    };
+    let emission = inst_data
+        .emission_rate
+        .checked_mul(inst_data.end_at - from)
+        .ok_or(ContractError::MathOverflow)?;
+    let amount = emission.saturating_sub(unemitted);
+    // a lower emission leaves part of the funded one unpaid
+    pool_data.undistributed_emission = pool_data
+        .undistributed_emission
+        .checked_add(unemitted.saturating_sub(emission))
+        .ok_or(ContractError::MathOverflow)?;
+    if amount > 0 {
+        // the pool has to receive the whole emission, transfer fees are on the funder
+        let gross_amount = get_pre_fee_amount(reward_token_mint_account, amount)?;
+        transfer_reward(
+            token_program_account,
+            reward_token_source_associated_account,
+            reward_token_mint_account,
+            reward_token_pool_associated_account,
+            account,
+            gross_amount,
+            &[],
+        )?;
+    }
+    pool_data.emission_rate = inst_data.emission_rate;
+    pool_data.emission_end_at = inst_data.end_at;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use crate::common::{
+    accrue_emission, get_emission_reward, get_pool_pda, is_continuous_pool, transfer_reward,
+    verify_program_account, verify_reward_ata_account, verify_reward_token_program,
+    verify_system_account,
+};
+use crate::schemas::states::pool::{Pool, POOL_SEED};
+use crate::schemas::states::staking_account::StakingAccount;
+use spl_associated_token_account::instruction as spl_instruction;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let dst_account = next_account_info(accounts_iter)?;
+    let reward_token_mint = next_account_info(accounts_iter)?;
+    let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    let mut staking_account = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
+    if pool_data.reward_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if !is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if staking_account.pool_pda_account != *pool_pda_account.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if staking_account.withdrawn_address != *dst_account.key {
+        return Err(ContractError::InvalidWithdrawnAddress.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint.key
+        || pool_data.reward_ata != *reward_token_pool_associated_account.key
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    verify_reward_ata_account(
+        &staking_account.withdrawn_address,
+        reward_token_dest_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    accrue_emission(&mut pool_data, now)?;
+    // withdrawn stakes were settled into accrued_reward
+    if staking_account.withdrawn_at == 0 {
+        let earned = get_emission_reward(staking_account.deposited_power, &pool_data)?;
+        staking_account.accrued_reward = staking_account
+            .accrued_reward
+            .checked_add(earned.saturating_sub(staking_account.reward_debt))
+            .ok_or(ContractError::MathOverflow)?;
+        staking_account.reward_debt = earned;
+    }
+    let reward_amount = staking_account.accrued_reward;
+    if reward_amount == 0 {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            &staking_account.withdrawn_address,
+            reward_token_mint.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                reward_token_dest_associated_account.clone(),
+                dst_account.clone(),
+                reward_token_mint.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    let (_, bump) = get_pool_pda(&pool_data.seed_name, &pool_data.seed_creator, program_id);
+    let pool_pda_signers_seeds: &[&[u8]; 4] = &[
+        &pool_data.seed_name[..],
+        POOL_SEED,
+        &pool_data.seed_creator.to_bytes(),
+        &[bump],
+    ];
+    transfer_reward(
+        token_program_account,
+        reward_token_pool_associated_account,
+        reward_token_mint,
+        reward_token_dest_associated_account,
+        pool_pda_account,
+        reward_amount,
+        &[pool_pda_signers_seeds],
+    )?;
+    staking_account.accrued_reward = 0;
+    staking_account.withdrawn_reward_amount = staking_account
+        .withdrawn_reward_amount
+        .checked_add(reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::common::{
+    get_payroll_pda, get_payroll_reward_pda, get_reward_vault_pda, get_rewarder_pda,
+    is_claim_expired, transfer_reward, verify_pool_creator, verify_program_account,
+    verify_reward_ata_account, verify_reward_token_program, verify_system_account,
+};
+use crate::error::ContractError;
+use crate::schemas::instructions::reward_redemption::RewardRedemption;
+use crate::schemas::states::payroll_reward::PayrollReward;
+use crate::schemas::states::pool::{Pool, REWADER_SEED};
+use crate::schemas::states::reward_vault::RewardVault;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRedemption,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    let payroll_reward_pda = next_account_info(accounts_iter)?;
+    let reward_vault_pda = next_account_info(accounts_iter)?;
+    let reward_pda = next_account_info(accounts_iter)?;
+    let reward_token_mint = next_account_info(accounts_iter)?;
+    let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    let index = inst_data.index;
+    let now = Clock::get()?.unix_timestamp as u64;
+    if !is_claim_expired(index, &pool_data, now) {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    let (expected_payroll, _) = get_payroll_pda(index, pool_pda_account.key, program_id);
+    if expected_payroll != *payroll_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let (expected_payroll_reward, _) =
+        get_payroll_reward_pda(payroll_pda.key, reward_token_mint.key, program_id);
+    let (expected_reward_vault, _) =
+        get_reward_vault_pda(pool_pda_account.key, reward_token_mint.key, program_id);
+    if expected_payroll_reward != *payroll_reward_pda.key
+        || expected_reward_vault != *reward_vault_pda.key
+    {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if payroll_reward_pda.data_len() == 0 {
+        return Err(ContractError::NoRewardPayroll.into());
+    }
+    verify_program_account(payroll_reward_pda, program_id)?;
+    verify_program_account(reward_vault_pda, program_id)?;
+    let mut payroll_reward = PayrollReward::try_from_slice(&payroll_reward_pda.data.borrow())?;
+    let mut reward_vault = RewardVault::try_from_slice(&reward_vault_pda.data.borrow())?;
+    let amount = payroll_reward
+        .total_reward_amount
+        .checked_sub(payroll_reward.reward_withdrawn_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    if amount == 0 {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    let (expected_rewarder, reward_bump) =
+        get_rewarder_pda(payroll_pda.key, pool_pda_account.key, program_id);
+    if expected_rewarder != *reward_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    verify_reward_ata_account(
+        reward_pda.key,
+        reward_token_pool_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    verify_reward_ata_account(
+        account.key,
+        reward_token_dest_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            account.key,
+            reward_token_mint.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                reward_token_dest_associated_account.clone(),
+                reward_token_mint.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    let rewarder_pda_signer_seeds: &[&[u8]; 4] = &[
+        REWADER_SEED,
+        &payroll_pda.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
+        &[reward_bump],
+    ];
+    transfer_reward(
+        token_program_account,
+        reward_token_pool_associated_account,
+        reward_token_mint,
+        reward_token_dest_associated_account,
+        reward_pda,
+        amount,
+        &[rewarder_pda_signer_seeds],
+    )?;
+    // the payroll reward is settled, claims of this mint now fail with
+    // ClaimExpired
+    payroll_reward.reward_withdrawn_amount = payroll_reward
+        .reward_withdrawn_amount
+        .checked_add(amount)
+        .ok_or(ContractError::MathOverflow)?;
+    payroll_reward.serialize(&mut &mut payroll_reward_pda.data.borrow_mut()[..])?;
+    reward_vault.reward_withdrawn_amount = reward_vault
+        .reward_withdrawn_amount
+        .checked_add(amount)
+        .ok_or(ContractError::MathOverflow)?;
+    reward_vault.serialize(&mut &mut reward_vault_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
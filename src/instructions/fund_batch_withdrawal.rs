@@ -0,0 +1,190 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use crate::common::{
+    accrue_emission, get_emission_reward, get_or_create_current_payroll_by_time, get_pool_pda,
+    get_payroll_power, is_continuous_pool, is_non_custodial_pool, remove_pending_power,
+    get_reward_decimals, update_payroll_reward, verify_ata_account, verify_program_account, verify_system_account,
+    verify_token_program,
+};
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::{Pool, POOL_SEED};
+use crate::schemas::states::staking_account::StakingAccount;
+use spl_associated_token_account::instruction as spl_instruction;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let withdraw_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_token_program(token_program_account)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.fund_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if is_non_custodial_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if !account.is_signer {
+        return Err(ContractError::InvalidDepositor.into());
+    }
+    let now = Clock::get()?.unix_timestamp as u64;
+    let continuous = is_continuous_pool(&pool_data);
+    if continuous {
+        accrue_emission(&mut pool_data, now)?;
+    }
+    let (_, bump) = get_pool_pda(&pool_data.seed_name, &pool_data.seed_creator, program_id);
+    let pool_pda_signers_seeds: &[&[u8]; 4] = &[
+        &pool_data.seed_name[..],
+        POOL_SEED,
+        &pool_data.seed_creator.to_bytes(),
+        &[bump],
+    ];
+    let mut batch_withdrawn_power: u64 = 0;
+    let mut batch_pending_power = 0;
+    let mut withdrawn_count = 0;
+    // four accounts per nft, until the remaining accounts run out
+    while let Ok(staking_token_mint_account) = next_account_info(accounts_iter) {
+        let staking_token_source_associated_account = next_account_info(accounts_iter)?;
+        let staking_token_dest_associated_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        verify_program_account(pda_account, program_id)?;
+        let mut pda_account_data = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
+        if pda_account_data.withdrawn_at > 0 {
+            return Err(ContractError::FundAlreadyWithdrawn.into());
+        }
+        if *account.key != pda_account_data.depositor {
+            return Err(ContractError::InvalidDepositor.into());
+        }
+        if pda_account_data.withdrawn_address != *withdraw_account.key {
+            return Err(ContractError::InvalidWithdrawnAddress.into());
+        }
+        if now < pda_account_data.locked_until {
+            return Err(ContractError::StillLocked.into());
+        }
+        if pda_account_data.pool_pda_account != *pool_pda_account.key
+            || pda_account_data.staking_token_mint_address != *staking_token_mint_account.key
+        {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        if pda_account_data.programmable {
+            return Err(ContractError::UnsupportedTokenStandard.into());
+        }
+        verify_ata_account(
+            pool_pda_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        verify_ata_account(
+            withdraw_account.key,
+            staking_token_dest_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        pda_account_data.withdrawn_at = now;
+        if continuous {
+            // settle what the stake earned, it stops accruing once withdrawn
+            let earned = get_emission_reward(pda_account_data.deposited_power, &pool_data)?;
+            pda_account_data.accrued_reward = pda_account_data
+                .accrued_reward
+                .checked_add(earned.saturating_sub(pda_account_data.reward_debt))
+                .ok_or(ContractError::MathOverflow)?;
+            pda_account_data.reward_debt = earned;
+        }
+        pda_account_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+        if staking_token_dest_associated_account.data_len() == 0 {
+            let create_token_account_ix = spl_instruction::create_associated_token_account(
+                account.key,
+                withdraw_account.key,
+                staking_token_mint_account.key,
+                token_program_account.key,
+            );
+            invoke(
+                &create_token_account_ix,
+                &[
+                    account.clone(),
+                    staking_token_dest_associated_account.clone(),
+                    withdraw_account.clone(),
+                    staking_token_mint_account.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                ],
+            )?;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_dest_associated_account.key,
+            pool_pda_account.key,
+            &[],
+            1,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                staking_token_source_associated_account.clone(),
+                staking_token_dest_associated_account.clone(),
+                pool_pda_account.clone(),
+                token_program_account.clone(),
+            ],
+            &[pool_pda_signers_seeds],
+        )?;
+        // token power may have changed since the deposit, remove what was added
+        batch_withdrawn_power = batch_withdrawn_power
+            .checked_add(pda_account_data.deposited_power)
+            .ok_or(ContractError::MathOverflow)?;
+        if pda_account_data.first_payroll_index == pool_data.pending_payroll_index {
+            batch_pending_power += pda_account_data.deposited_power;
+        }
+        withdrawn_count += 1;
+    }
+    if withdrawn_count == 0 {
+        return Err(ContractError::InvalidDepositAmount.into());
+    }
+    // pool and payroll are written once for the whole batch
+    pool_data.total_deposited_power = pool_data
+        .total_deposited_power
+        .checked_sub(batch_withdrawn_power)
+        .ok_or(ContractError::MathOverflow)?;
+    let pending_payroll_index = pool_data.pending_payroll_index;
+    remove_pending_power(&mut pool_data, batch_pending_power, pending_payroll_index);
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    if continuous {
+        return Ok(());
+    }
+    let (_, current_payroll_index) = get_or_create_current_payroll_by_time(
+        now,
+        program_id,
+        account,
+        pool_pda_account,
+        payroll_pda,
+        system_program_account,
+        pool_data.clone(),
+    )?;
+    let mut current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+    let total_reward_amount = current_payroll_data.total_reward_amount;
+    update_payroll_reward(
+        &mut current_payroll_data,
+        get_payroll_power(&pool_data, current_payroll_index),
+        total_reward_amount,
+        get_reward_decimals(&pool_data),
+    )?;
+    current_payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+
+    Ok(())
+}
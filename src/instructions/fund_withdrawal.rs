@@ -1,7 +1,9 @@
 use crate::common::{
-    get_or_create_current_payroll,
-    recalculate_reward_rate,
-    verify_program_account, verify_system_account, TOKEN_DATA_ACCOUNT_TYPE,
+    get_or_create_current_payroll, get_payroll_power, remove_pending_power,
+    is_non_custodial_pool, next_programmable_accounts,
+    get_reward_decimals, transfer_programmable_nft, update_payroll_reward,
+    verify_ata_account, verify_program_account, verify_system_account, verify_token_program,
+    accrue_emission, get_emission_reward, is_continuous_pool,
 };
 use crate::error::ContractError;
 use crate::schemas::states::payroll::Payroll;
@@ -18,18 +20,16 @@ use solana_program::{
     pubkey::Pubkey,
     sysvar::Sysvar,
 };
-use crate::schemas::states::token_data::{
-    TokenData,
-    TOKEN_DATA_SEED
-};
-
 
+use mpl_token_metadata::{
+    ID as MPL_PROGRAM_ID,
+    instructions::{ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts},
+};
 use spl_associated_token_account::instruction as spl_instruction;
 // Program entrypoint's implementation
 pub fn process_instruction<'a>(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &'a [AccountInfo<'a>], // The account to say hello to
-    _instruction_data: &[u8],
+    accounts: &[AccountInfo<'a>], // The account to say hello to
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
@@ -39,35 +39,19 @@ pub fn process_instruction<'a>(
     let staking_token_mint_account = next_account_info(accounts_iter)?;
     let staking_token_source_associated_account = next_account_info(accounts_iter)?;
     let staking_token_dest_associated_account = next_account_info(accounts_iter)?;
-    let staking_token_data_pda = next_account_info(accounts_iter)?;
     let payroll_pda = next_account_info(accounts_iter)?;
     let token_program_account = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
     // check for account
     // let pool_pda_account_data = pool_pda_account.data.borrow();
-    verify_system_account(&account)?;
+    verify_system_account(account)?;
     verify_program_account(pool_pda_account, program_id)?;
     verify_program_account(pda_account, program_id)?;
-    // verify_program_account(staking_token_data_pda, program_id)?;
-    let token_data_seeeds = &[
-        TOKEN_DATA_SEED,
-        &staking_token_mint_account.key.to_bytes(),
-    ];
-    let (expected_token_data_pda, _bump) = Pubkey::find_program_address(token_data_seeeds, program_id);
-    if expected_token_data_pda != *staking_token_data_pda.key {
-        return Err(ContractError::InvalidPdaAccount.into());
-    }
-    let token_data = match staking_token_data_pda.owner != program_id {
-        true => TokenData {
-            account_type: TOKEN_DATA_ACCOUNT_TYPE,
-            power: 1,
-            token_mint_address: *staking_token_mint_account.key,
-        },
-        false => TokenData::try_from_slice(&staking_token_data_pda.data.borrow())?
-    };
-  
-    // let token_data = TokenData::try_from_slice(&staking_token_data_pda.data.borrow())?;
+    verify_token_program(token_program_account)?;
     let mut updated_pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if updated_pool_data.fund_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
     let mut pda_account_data = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
     let withdrawn_address = pda_account_data.withdrawn_address;
     let clock = Clock::get()?;
@@ -86,15 +70,40 @@ pub fn process_instruction<'a>(
     if pda_account_data.withdrawn_address != *withdraw_account.key {
         return Err(ContractError::InvalidWithdrawnAddress.into());
     }
+    if now < pda_account_data.locked_until {
+        return Err(ContractError::StillLocked.into());
+    }
     pda_account_data.withdrawn_at = now;
     // early withdrawl results in penalty
     msg!("Checking pool pda");
-    if pda_account_data.pool_pda_account != *pool_pda_account.key {
+    if pda_account_data.pool_pda_account != *pool_pda_account.key
+        || pda_account_data.staking_token_mint_address != *staking_token_mint_account.key
+    {
         return Err(ContractError::InvalidPdaAccount.into());
     }
 
+    let continuous = is_continuous_pool(&updated_pool_data);
+    if continuous {
+        // settle what the stake earned, it stops accruing once withdrawn
+        accrue_emission(&mut updated_pool_data, now)?;
+        let earned = get_emission_reward(pda_account_data.deposited_power, &updated_pool_data)?;
+        pda_account_data.accrued_reward = pda_account_data
+            .accrued_reward
+            .checked_add(earned.saturating_sub(pda_account_data.reward_debt))
+            .ok_or(ContractError::MathOverflow)?;
+        pda_account_data.reward_debt = earned;
+    }
     pda_account_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
-    updated_pool_data.total_deposited_power -= token_data.power;
+    // token power may have changed since the deposit, remove what was added
+    updated_pool_data.total_deposited_power = updated_pool_data
+        .total_deposited_power
+        .checked_sub(pda_account_data.deposited_power)
+        .ok_or(ContractError::MathOverflow)?;
+    remove_pending_power(
+        &mut updated_pool_data,
+        pda_account_data.deposited_power,
+        pda_account_data.first_payroll_index,
+    );
     updated_pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
     // now transfer
     let ata_dest_account_data_len = staking_token_dest_associated_account.data_len();
@@ -104,9 +113,9 @@ pub fn process_instruction<'a>(
     //   &pool_creator_account.key.to_bytes(),
     // ];
     let pool_pda_account_seeds: &[&[u8]; 3] = &[
-        &updated_pool_data.name[..],
+        &updated_pool_data.seed_name[..],
         POOL_SEED,
-        &updated_pool_data.creator.to_bytes(),
+        &updated_pool_data.seed_creator.to_bytes(),
     ];
     let (_, bump) = Pubkey::find_program_address(pool_pda_account_seeds, program_id);
     // let pool_signers_seeds: &[&[u8]; 4] = &[
@@ -116,69 +125,140 @@ pub fn process_instruction<'a>(
     //     &[bump],
     // ];
     let pool_pda_signers_seeds: &[&[u8]; 4] = &[
-        &updated_pool_data.name[..],
+        &updated_pool_data.seed_name[..],
         POOL_SEED,
-        &updated_pool_data.creator.to_bytes(),
+        &updated_pool_data.seed_creator.to_bytes(),
         &[bump],
     ];
-    // msg!("ata dst address: {:?}, {:?}" ,staking_account.withdrawn_address, dst_account.key);
-    if ata_dest_account_data_len <= 0 {
-        let create_token_account_ix = spl_instruction::create_associated_token_account(
-            &account.key,
-            &withdrawn_address,
-            &staking_token_mint_account.key,
-            // &token_program_account.key,
-        );
+    if pda_account_data.programmable {
+        let edition_account = next_account_info(accounts_iter)?;
+        let token_metadata_program = next_account_info(accounts_iter)?;
+        let meta_pda = next_account_info(accounts_iter)?;
+        let programmable_accounts = next_programmable_accounts(accounts_iter)?;
+        transfer_programmable_nft(
+            token_metadata_program,
+            staking_token_source_associated_account,
+            pool_pda_account,
+            staking_token_dest_associated_account,
+            withdraw_account,
+            staking_token_mint_account,
+            meta_pda,
+            edition_account,
+            pool_pda_account,
+            account,
+            system_program_account,
+            token_program_account,
+            &programmable_accounts,
+            &[pool_pda_signers_seeds],
+        )?;
+    } else if is_non_custodial_pool(&updated_pool_data) {
+        // the nft never left the depositor wallet
+        let edition_account = next_account_info(accounts_iter)?;
+        let token_metadata_program = next_account_info(accounts_iter)?;
+        if *token_metadata_program.key != MPL_PROGRAM_ID {
+            return Err(ContractError::InvalidProgramAccount.into());
+        }
+        verify_ata_account(
+            &depositor,
+            staking_token_source_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        ThawDelegatedAccountCpi::new(
+            token_metadata_program,
+            ThawDelegatedAccountCpiAccounts {
+                delegate: pool_pda_account,
+                token_account: staking_token_source_associated_account,
+                edition: edition_account,
+                mint: staking_token_mint_account,
+                token_program: token_program_account,
+            },
+        ).invoke_signed(&[pool_pda_signers_seeds])?;
+        let ix = spl_token::instruction::revoke(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            account.key,
+            &[],
+        )?;
         invoke(
-            &create_token_account_ix,
+            &ix,
             &[
+                staking_token_source_associated_account.clone(),
                 account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    } else {
+        // the pool signs the transfer, it must only move the staked nft
+        verify_ata_account(
+            pool_pda_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        verify_ata_account(
+            &withdrawn_address,
+            staking_token_dest_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        if ata_dest_account_data_len == 0 {
+            let create_token_account_ix = spl_instruction::create_associated_token_account(
+                account.key,
+                &withdrawn_address,
+                staking_token_mint_account.key,
+                token_program_account.key,
+            );
+            invoke(
+                &create_token_account_ix,
+                &[
+                    account.clone(),
+                    staking_token_dest_associated_account.clone(),
+                    withdraw_account.clone(),
+                    staking_token_mint_account.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                ],
+            )?;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_dest_associated_account.key,
+            pool_pda_account.key,
+            &[],
+            1,
+        )?;
+        invoke_signed(
+            &ix,
+            &[
+                staking_token_source_associated_account.clone(),
                 staking_token_dest_associated_account.clone(),
-                withdraw_account.clone(),
-                staking_token_mint_account.clone(),
-                system_program_account.clone(),
+                pool_pda_account.clone(),
                 token_program_account.clone(),
             ],
+            &[pool_pda_signers_seeds],
         )?;
     }
-    let ix = spl_token::instruction::transfer(
-        &token_program_account.key,
-        &staking_token_source_associated_account.key,
-        &staking_token_dest_associated_account.key,
-        &pool_pda_account.key,
-        &[],
-        1,
-    )?;
-    invoke_signed(
-        &ix,
-        &[
-            staking_token_source_associated_account.clone(),
-            staking_token_dest_associated_account.clone(),
-            pool_pda_account.clone(),
-            token_program_account.clone(),
-        ],
-        &[pool_pda_signers_seeds],
-    )?;
-    
-    match get_or_create_current_payroll(
+
+    if continuous {
+        return Ok(());
+    }
+    let (_, current_payroll_index) = get_or_create_current_payroll(
         program_id,
         account,
         pool_pda_account,
         payroll_pda,
         system_program_account,
         updated_pool_data.clone(),
-    ) {
-        Ok(p) => p,
-        Err(err) => return Err(err),
-    };
+    )?;
     if payroll_pda.data_len() > 0 {
         let mut current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
-        current_payroll_data.total_deposited_power = updated_pool_data.total_deposited_power;
-        let rate_reward = recalculate_reward_rate(
-            current_payroll_data.total_deposited_power,
-            current_payroll_data.total_reward_amount,
-        );
-        current_payroll_data.rate_reward = rate_reward;
+        let total_reward_amount = current_payroll_data.total_reward_amount;
+        update_payroll_reward(
+            &mut current_payroll_data,
+            // deposits made while the payroll runs do not earn from it
+            get_payroll_power(&updated_pool_data, current_payroll_index),
+            total_reward_amount,
+            get_reward_decimals(&updated_pool_data),
+        )?;
         current_payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
 
     }
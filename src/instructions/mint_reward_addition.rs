@@ -0,0 +1,207 @@
+use crate::common::{
+    get_or_create_payroll_by_index, get_payroll_power, get_payroll_reward_pda, is_continuous_pool, is_past_pool_end, get_rewarder_pda,
+    transfer_reward, verify_program_account, verify_reward_ata_account,
+    verify_reward_token_program, verify_system_account, PAYROLL_REWARD_ACCOUNT_TYPE, POOL_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::payroll_reward::{
+    PayrollReward, PAYROLL_REWARD_PDA_LEN, PAYROLL_REWARD_SEED,
+};
+use crate::schemas::states::reward_vault::RewardVault;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::{
+    instruction as spl_instruction,
+};
+use std::convert::TryInto;
+
+use crate::schemas::instructions::reward_addition::RewardAddition;
+
+use crate::error::ContractError;
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardAddition,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let reward_vault_pda = next_account_info(accounts_iter)?;
+    let rewarder_pda = next_account_info(accounts_iter)?;
+    let reward_token_mint_account = next_account_info(accounts_iter)?;
+    let reward_token_source_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    let payroll_reward_pda = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(reward_vault_pda, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    let mut reward_vault = RewardVault::try_from_slice(&reward_vault_pda.data.borrow())?;
+    if reward_vault.pool_pda_account != *pool_pda_account.key
+        || reward_vault.reward_token_mint_address != *reward_token_mint_account.key
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
+    verify_reward_ata_account(
+        account.key,
+        reward_token_source_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    let current_payroll_index = inst_data.payroll_index;
+    if is_past_pool_end(current_payroll_index, &pool_data) {
+        return Err(ContractError::PayrollPastPoolEnd.into());
+    }
+    let new_payroll = payroll_pda.data_len() == 0;
+    if !new_payroll {
+        verify_program_account(payroll_pda, program_id)?;
+        if Payroll::try_from_slice(&payroll_pda.data.borrow())?.sealed {
+            return Err(ContractError::PayrollSealed.into());
+        }
+    }
+    if get_or_create_payroll_by_index(
+        current_payroll_index,
+        program_id,
+        account,
+        pool_pda_account,
+        payroll_pda,
+        system_program_account,
+    ).is_err() {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if new_payroll {
+        // same payroll the pool reward token would have opened, without reward
+        let payroll_account_data = Payroll {
+            account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+            total_deposited_power: get_payroll_power(&pool_data, current_payroll_index),
+            total_reward_amount: 0,
+            rate_reward: 0,
+            reward_remainder: 0,
+            claimed_power: 0,
+            sealed: false,
+            reward_withdrawn_amount: 0,
+            index: current_payroll_index,
+            start_at: pool_data.start_at,
+            claimable_after: pool_data.start_at + current_payroll_index * pool_data.reward_period,
+            pool_pda_account: *pool_pda_account.key,
+            creator: *account.key,
+        };
+        payroll_account_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+    }
+    let (expected_rewarder, _bump) =
+        get_rewarder_pda(payroll_pda.key, pool_pda_account.key, program_id);
+    if expected_rewarder != *rewarder_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    verify_reward_ata_account(
+        &expected_rewarder,
+        reward_token_dest_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    let (expected_payroll_reward, payroll_reward_bump) = get_payroll_reward_pda(
+        payroll_pda.key,
+        reward_token_mint_account.key,
+        program_id,
+    );
+    if expected_payroll_reward != *payroll_reward_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let mut payroll_reward = match payroll_reward_pda.data_len() {
+        0 => {
+            let payroll_reward_signer_seeds: &[&[u8]; 4] = &[
+                PAYROLL_REWARD_SEED,
+                &payroll_pda.key.to_bytes(),
+                &reward_token_mint_account.key.to_bytes(),
+                &[payroll_reward_bump],
+            ];
+            let lamports_required = Rent::get()?.minimum_balance(PAYROLL_REWARD_PDA_LEN);
+            let create_pda_account_ix = system_instruction::create_account(
+                account.key,
+                payroll_reward_pda.key,
+                lamports_required,
+                PAYROLL_REWARD_PDA_LEN.try_into().unwrap(),
+                program_id,
+            );
+            invoke_signed(
+                &create_pda_account_ix,
+                &[
+                    account.clone(),
+                    payroll_reward_pda.clone(),
+                    system_program_account.clone(),
+                ],
+                &[payroll_reward_signer_seeds],
+            )?;
+            PayrollReward {
+                account_type: PAYROLL_REWARD_ACCOUNT_TYPE,
+                payroll_pda_account: *payroll_pda.key,
+                reward_token_mint_address: *reward_token_mint_account.key,
+                index: current_payroll_index,
+                total_reward_amount: 0,
+                reward_withdrawn_amount: 0,
+            }
+        }
+        _ => PayrollReward::try_from_slice(&payroll_reward_pda.data.borrow())?,
+    };
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            rewarder_pda.key,
+            reward_token_mint_account.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+              account.clone(),
+              reward_token_dest_associated_account.clone(),
+              rewarder_pda.clone(),
+              reward_token_mint_account.clone(),
+              system_program_account.clone(),
+              token_program_account.clone(),
+            ],
+        )?;
+    }
+    // only what reached the rewarder after transfer fees can be paid out
+    let amount = transfer_reward(
+        token_program_account,
+        reward_token_source_associated_account,
+        reward_token_mint_account,
+        reward_token_dest_associated_account,
+        account,
+        inst_data.amount,
+        &[],
+    )?;
+    payroll_reward.total_reward_amount = payroll_reward
+        .total_reward_amount
+        .checked_add(amount)
+        .ok_or(ContractError::MathOverflow)?;
+    payroll_reward.serialize(&mut &mut payroll_reward_pda.data.borrow_mut()[..])?;
+    reward_vault.total_reward_amount = reward_vault
+        .total_reward_amount
+        .checked_add(amount)
+        .ok_or(ContractError::MathOverflow)?;
+    reward_vault.serialize(&mut &mut reward_vault_pda.data.borrow_mut()[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,261 @@
+use crate::common::{
+    get_current_payroll_index, get_mint_staking_payroll_pda, get_or_create_payroll_by_index,
+    get_payroll_claimable_after, get_payroll_reward, get_payroll_reward_pda, get_rewarder_pda,
+    get_reward_decimals, is_claim_expired, recalculate_reward_rate, transfer_reward, verify_program_account, verify_reward_ata_account,
+    verify_reward_token_program, verify_system_account, STAKING_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::error::ContractError;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::payroll_reward::PayrollReward;
+use crate::schemas::states::pool::{Pool, REWADER_SEED};
+use crate::schemas::states::reward_vault::RewardVault;
+use crate::schemas::states::staking_account::StakingAccount;
+use crate::schemas::states::staking_payroll::{
+    StakingPayroll, STAKING_PAYROLL_PDA_LEN, STAKING_PAYROLL_SEED,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+use std::convert::TryInto;
+
+use crate::schemas::instructions::reward_redemption::RewardRedemption;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRedemption,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let dst_account = next_account_info(accounts_iter)?;
+    let staking_payroll_account = next_account_info(accounts_iter)?;
+    let reward_pda = next_account_info(accounts_iter)?;
+    let reward_token_mint = next_account_info(accounts_iter)?;
+    let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    let payroll_reward_pda = next_account_info(accounts_iter)?;
+    let reward_vault_pda = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    if payroll_pda.data_len() == 0 || payroll_reward_pda.data_len() == 0 {
+        return Err(ContractError::NoRewardPayroll.into());
+    }
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(pda_account, program_id)?;
+    verify_program_account(payroll_pda, program_id)?;
+    verify_program_account(payroll_reward_pda, program_id)?;
+    verify_program_account(reward_vault_pda, program_id)?;
+    let payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+    let mut payroll_reward = PayrollReward::try_from_slice(&payroll_reward_pda.data.borrow())?;
+    let mut reward_vault = RewardVault::try_from_slice(&reward_vault_pda.data.borrow())?;
+    let staking_account = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.reward_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if staking_account.pool_pda_account != *pool_pda_account.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if reward_vault.pool_pda_account != *pool_pda_account.key
+        || reward_vault.reward_token_mint_address != *reward_token_mint.key
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    if staking_account.withdrawn_address != *dst_account.key {
+        return Err(ContractError::InvalidWithdrawnAddress.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    verify_reward_ata_account(
+        reward_pda.key,
+        reward_token_pool_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    verify_reward_ata_account(
+        &staking_account.withdrawn_address,
+        reward_token_dest_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    let index = inst_data.index;
+    if staking_account.first_payroll_index > index {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    if get_or_create_payroll_by_index(
+        index,
+        program_id,
+        account,
+        pool_pda_account,
+        payroll_pda,
+        system_program_account,
+    ).is_err() {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let (expected_payroll_reward, _bump) =
+        get_payroll_reward_pda(payroll_pda.key, reward_token_mint.key, program_id);
+    if expected_payroll_reward != *payroll_reward_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let (expected_rewarder, reward_bump) =
+        get_rewarder_pda(payroll_pda.key, pool_pda_account.key, program_id);
+    if expected_rewarder != *reward_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let rewarder_pda_signer_seeds: &[&[u8]; 4] = &[
+        REWADER_SEED,
+        &payroll_pda.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
+        &[reward_bump],
+    ];
+    let now = Clock::get()?.unix_timestamp as u64;
+    if now < get_payroll_claimable_after(payroll_data.index, &pool_data) {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    // registered mints expire with the payroll, see SweepExpiredMintReward
+    if is_claim_expired(payroll_data.index, &pool_data, now) {
+        return Err(ContractError::ClaimExpired.into());
+    }
+    if !payroll_data.sealed {
+        return Err(ContractError::PayrollNotSealed.into());
+    }
+    // fund withdrawn
+    if staking_account.withdrawn_at > 0 {
+        let latest_payroll_index = get_current_payroll_index(
+            staking_account.withdrawn_at,
+            pool_data.reward_period,
+            pool_data.start_at,
+        );
+        if latest_payroll_index.checked_sub(1).ok_or(ContractError::MathOverflow)? < payroll_data.index {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+    }
+    let (staking_payroll_pda, staking_payroll_bump) = get_mint_staking_payroll_pda(
+        payroll_data.index,
+        pool_pda_account.key,
+        pda_account.key,
+        reward_token_mint.key,
+        program_id,
+    );
+    if staking_payroll_pda != *staking_payroll_account.key {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    let mut total_withdrawn_reward = 0;
+    if staking_payroll_account.data_len() > 0 {
+        // staking payroll accounts are allocated larger than the struct
+        let staking_payroll_data =
+            StakingPayroll::deserialize(&mut &staking_payroll_account.data.borrow()[..])?;
+        total_withdrawn_reward = staking_payroll_data.reward_withdrawn_amount;
+    } else {
+        let parsed_index = payroll_data.index.to_string();
+        let staking_payroll_signers_seeds: &[&[u8]; 6] = &[
+            STAKING_PAYROLL_SEED,
+            parsed_index.as_bytes(),
+            &pool_pda_account.key.to_bytes(),
+            &pda_account.key.to_bytes(),
+            &reward_token_mint.key.to_bytes(),
+            &[staking_payroll_bump],
+        ];
+        let lamports_required = Rent::get()?.minimum_balance(STAKING_PAYROLL_PDA_LEN);
+        let create_staking_payroll_pda_account_ix = system_instruction::create_account(
+            account.key,
+            staking_payroll_account.key,
+            lamports_required,
+            STAKING_PAYROLL_PDA_LEN.try_into().unwrap(),
+            program_id,
+        );
+        invoke_signed(
+            &create_staking_payroll_pda_account_ix,
+            &[
+                account.clone(),
+                staking_payroll_account.clone(),
+                system_program_account.clone(),
+            ],
+            &[staking_payroll_signers_seeds],
+        )?;
+    }
+    // the rate follows the payroll power, which deposits keep up to date
+    let reward_decimals = get_reward_decimals(&pool_data);
+    let rate_reward = recalculate_reward_rate(
+        payroll_data.total_deposited_power,
+        payroll_reward.total_reward_amount,
+        reward_decimals,
+    )?;
+    let payroll_reward_left = payroll_reward
+        .total_reward_amount
+        .saturating_sub(payroll_reward.reward_withdrawn_amount);
+    let reward_amount = std::cmp::min(
+        get_payroll_reward(staking_account.deposited_power, rate_reward, reward_decimals)?
+            .saturating_sub(total_withdrawn_reward),
+        payroll_reward_left,
+    );
+    if reward_amount == 0 {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            &staking_account.withdrawn_address,
+            reward_token_mint.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                reward_token_dest_associated_account.clone(),
+                dst_account.clone(),
+                reward_token_mint.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    transfer_reward(
+        token_program_account,
+        reward_token_pool_associated_account,
+        reward_token_mint,
+        reward_token_dest_associated_account,
+        reward_pda,
+        reward_amount,
+        &[rewarder_pda_signer_seeds],
+    )?;
+    payroll_reward.reward_withdrawn_amount = payroll_reward
+        .reward_withdrawn_amount
+        .checked_add(reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    payroll_reward.serialize(&mut &mut payroll_reward_pda.data.borrow_mut()[..])?;
+    reward_vault.reward_withdrawn_amount = reward_vault
+        .reward_withdrawn_amount
+        .checked_add(reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    reward_vault.serialize(&mut &mut reward_vault_pda.data.borrow_mut()[..])?;
+    let updated_staking_payroll_data = StakingPayroll {
+        account_type: STAKING_PAYROLL_ACCOUNT_TYPE,
+        staking_pda_account: *pda_account.key,
+        deposited_power: staking_account.deposited_power,
+        total_pool_deposited_power: payroll_data.total_deposited_power,
+        total_reward_amount: payroll_reward.total_reward_amount,
+        reward_withdrawn_amount: total_withdrawn_reward
+            .checked_add(reward_amount)
+            .ok_or(ContractError::MathOverflow)?,
+        index: payroll_data.index,
+        withdrawn_at: now,
+    };
+    updated_staking_payroll_data
+        .serialize(&mut &mut staking_payroll_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
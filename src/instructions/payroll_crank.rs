@@ -0,0 +1,103 @@
+use crate::common::{
+    get_current_payroll_index, get_or_create_payroll_by_index, get_payroll_claimable_after,
+    get_payroll_power, get_reward_decimals, is_continuous_pool, is_past_pool_end, update_payroll_reward,
+    verify_program_account, verify_system_account, POOL_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::error::ContractError;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::Pool;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let current_payroll_pda = next_account_info(accounts_iter)?;
+    let next_payroll_pda = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    let now = Clock::get()?.unix_timestamp as u64;
+    if now < pool_data.start_at {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    let current_payroll_index =
+        get_current_payroll_index(now, pool_data.reward_period, pool_data.start_at);
+    let reward_decimals = get_reward_decimals(&pool_data);
+    let mut created = false;
+    for (payroll_pda, index) in [
+        (current_payroll_pda, current_payroll_index),
+        (next_payroll_pda, current_payroll_index + 1),
+    ] {
+        // nothing runs past the end of the pool, nor is paid for
+        if is_past_pool_end(index, &pool_data) {
+            continue;
+        }
+        let new_payroll = payroll_pda.data_len() == 0;
+        if get_or_create_payroll_by_index(
+            index,
+            program_id,
+            account,
+            pool_pda_account,
+            payroll_pda,
+            system_program_account,
+        ).is_err() {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        let mut payroll_data = match new_payroll {
+            true => Payroll {
+                account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+                total_deposited_power: 0,
+                total_reward_amount: 0,
+                rate_reward: 0,
+                reward_remainder: 0,
+                claimed_power: 0,
+                sealed: false,
+                reward_withdrawn_amount: 0,
+                index,
+                start_at: pool_data.start_at,
+                claimable_after: get_payroll_claimable_after(index, &pool_data),
+                pool_pda_account: *pool_pda_account.key,
+                creator: *account.key,
+            },
+            false => {
+                verify_program_account(payroll_pda, program_id)?;
+                Payroll::try_from_slice(&payroll_pda.data.borrow())?
+            }
+        };
+        // neither payroll has ended, both follow the power of the pool
+        let total_reward_amount = payroll_data.total_reward_amount;
+        update_payroll_reward(
+            &mut payroll_data,
+            get_payroll_power(&pool_data, index),
+            total_reward_amount,
+            reward_decimals,
+        )?;
+        payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+        created |= new_payroll;
+    }
+    // only cranks that open a payroll are paid, so the budget cannot be
+    // drained by calling it again
+    let crank_tip = pool_data.crank_tip;
+    if created && crank_tip > 0 && pool_data.crank_budget >= crank_tip {
+        pool_data.crank_budget -= crank_tip;
+        pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+        **pool_pda_account.try_borrow_mut_lamports()? -= crank_tip;
+        **account.try_borrow_mut_lamports()? += crank_tip;
+    }
+    Ok(())
+}
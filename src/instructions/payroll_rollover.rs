@@ -0,0 +1,182 @@
+use crate::common::{
+    get_or_create_payroll_by_index, get_payroll_claimable_after, get_payroll_pda, get_rewarder_pda,
+    get_payroll_power, get_reward_decimals, is_continuous_pool, is_past_pool_end, is_payroll_fully_claimed, transfer_reward,
+    update_payroll_reward, verify_pool_creator,
+    verify_program_account, verify_reward_ata_account, verify_reward_token_program,
+    verify_system_account, POOL_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::error::ContractError;
+use crate::schemas::instructions::payroll_rollover::PayrollRolloverIns;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::{Pool, REWADER_SEED};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PayrollRolloverIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let from_payroll_pda = next_account_info(accounts_iter)?;
+    let from_rewarder_pda = next_account_info(accounts_iter)?;
+    let from_reward_token_associated_account = next_account_info(accounts_iter)?;
+    let to_payroll_pda = next_account_info(accounts_iter)?;
+    let to_rewarder_pda = next_account_info(accounts_iter)?;
+    let to_reward_token_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_mint_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    if is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint_account.key {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
+    let PayrollRolloverIns { from_index, to_index } = inst_data;
+    let now = Clock::get()?.unix_timestamp as u64;
+    // the source payroll is over, the target one can still be earned
+    if now < get_payroll_claimable_after(from_index, &pool_data)
+        || now >= get_payroll_claimable_after(to_index, &pool_data)
+    {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    if is_past_pool_end(to_index, &pool_data) {
+        return Err(ContractError::PayrollPastPoolEnd.into());
+    }
+    let (expected_from_payroll, _) = get_payroll_pda(from_index, pool_pda_account.key, program_id);
+    if expected_from_payroll != *from_payroll_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if from_payroll_pda.data_len() == 0 {
+        return Err(ContractError::NoRewardPayroll.into());
+    }
+    verify_program_account(from_payroll_pda, program_id)?;
+    let mut from_payroll_data = Payroll::try_from_slice(&from_payroll_pda.data.borrow())?;
+    if !from_payroll_data.sealed {
+        return Err(ContractError::PayrollNotSealed.into());
+    }
+    // with no power in the payroll its rate is 0 and nobody can claim it, once
+    // every stake has claimed only the rounding remainder is left
+    let amount = from_payroll_data.total_reward_amount - from_payroll_data.reward_withdrawn_amount;
+    if !is_payroll_fully_claimed(&from_payroll_data) || amount == 0 {
+        return Err(ContractError::NoRolloverReward.into());
+    }
+    let (expected_from_rewarder, from_rewarder_bump) =
+        get_rewarder_pda(from_payroll_pda.key, pool_pda_account.key, program_id);
+    if expected_from_rewarder != *from_rewarder_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    verify_reward_ata_account(
+        from_rewarder_pda.key,
+        from_reward_token_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    let new_payroll = to_payroll_pda.data_len() == 0;
+    if get_or_create_payroll_by_index(
+        to_index,
+        program_id,
+        account,
+        pool_pda_account,
+        to_payroll_pda,
+        system_program_account,
+    ).is_err() {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let (expected_to_rewarder, _) =
+        get_rewarder_pda(to_payroll_pda.key, pool_pda_account.key, program_id);
+    if expected_to_rewarder != *to_rewarder_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    verify_reward_ata_account(
+        to_rewarder_pda.key,
+        to_reward_token_associated_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
+    )?;
+    if to_reward_token_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            to_rewarder_pda.key,
+            reward_token_mint_account.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                to_reward_token_associated_account.clone(),
+                to_rewarder_pda.clone(),
+                reward_token_mint_account.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    let from_rewarder_signer_seeds: &[&[u8]; 4] = &[
+        REWADER_SEED,
+        &from_payroll_pda.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
+        &[from_rewarder_bump],
+    ];
+    let received_amount = transfer_reward(
+        token_program_account,
+        from_reward_token_associated_account,
+        reward_token_mint_account,
+        to_reward_token_associated_account,
+        from_rewarder_pda,
+        amount,
+        &[from_rewarder_signer_seeds],
+    )?;
+    from_payroll_data.total_reward_amount -= amount;
+    from_payroll_data.reward_remainder = 0;
+    from_payroll_data.serialize(&mut &mut from_payroll_pda.data.borrow_mut()[..])?;
+
+    // the target payroll is credited the same way AddReward does
+    let mut to_payroll_data = match new_payroll {
+        true => Payroll {
+            account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+            total_deposited_power: 0,
+            total_reward_amount: 0,
+            rate_reward: 0,
+            reward_remainder: 0,
+            claimed_power: 0,
+            sealed: false,
+            reward_withdrawn_amount: 0,
+            index: to_index,
+            start_at: pool_data.start_at,
+            claimable_after: get_payroll_claimable_after(to_index, &pool_data),
+            pool_pda_account: *pool_pda_account.key,
+            creator: *account.key,
+        },
+        false => Payroll::try_from_slice(&to_payroll_pda.data.borrow())?,
+    };
+    let total_reward_amount = to_payroll_data
+        .total_reward_amount
+        .checked_add(received_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    update_payroll_reward(
+        &mut to_payroll_data,
+        get_payroll_power(&pool_data, to_index),
+        total_reward_amount,
+        get_reward_decimals(&pool_data),
+    )?;
+    to_payroll_data.serialize(&mut &mut to_payroll_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use crate::common::{
+    get_payroll_claimable_after, get_payroll_pda, get_reward_decimals, is_continuous_pool,
+    update_payroll_reward, verify_program_account, verify_system_account,
+};
+use crate::error::ContractError;
+use crate::schemas::instructions::reward_redemption::RewardRedemption;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::Pool;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRedemption,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    let index = inst_data.index;
+    let now = Clock::get()?.unix_timestamp as u64;
+    // deposits and withdrawals stop touching a payroll once it has ended
+    if now < get_payroll_claimable_after(index, &pool_data) {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    let (expected_payroll, _) = get_payroll_pda(index, pool_pda_account.key, program_id);
+    if expected_payroll != *payroll_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if payroll_pda.data_len() == 0 {
+        return Err(ContractError::NoRewardPayroll.into());
+    }
+    verify_program_account(payroll_pda, program_id)?;
+    let mut payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+    if payroll_data.sealed {
+        return Err(ContractError::PayrollSealed.into());
+    }
+    let total_deposited_power = payroll_data.total_deposited_power;
+    let total_reward_amount = payroll_data.total_reward_amount;
+    update_payroll_reward(
+        &mut payroll_data,
+        total_deposited_power,
+        total_reward_amount,
+        get_reward_decimals(&pool_data),
+    )?;
+    payroll_data.sealed = true;
+    payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
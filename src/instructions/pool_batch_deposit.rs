@@ -0,0 +1,278 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use std::convert::TryInto;
+use crate::common::{
+    accrue_emission, add_pending_power, get_current_payroll_index, get_emission_reward, get_lock_tier,
+    get_or_create_next_payroll_by_time, is_continuous_pool, is_pool_ended, get_staking_pda, get_token_data_pda,
+    get_token_power, is_non_custodial_pool, is_programmable, get_reward_decimals, verify_ata_account,
+    update_payroll_reward,
+    verify_depositable, get_pool_collections, get_collection_power,
+    get_nft_metadata,
+    verify_program_account, verify_system_account, verify_token_program, MULTIPLIER_BASE, POOL_PAYROLL_ACCOUNT_TYPE,
+    STAKING_ACCOUNT_TYPE,
+};
+use crate::schemas::instructions::pool_deposit::PoolDepositIns;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::staking_account::{StakingAccount, STAKING_PDA_LEN, STAKING_SEED};
+use spl_associated_token_account::instruction as spl_instruction;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PoolDepositIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let payroll_pda = next_account_info(accounts_iter)?;
+    let power_rules_pda = next_account_info(accounts_iter)?;
+    let pool_collections_pda = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_token_program(token_program_account)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    // delegated freezes need the edition of every nft, deposit them one by one
+    if is_non_custodial_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    // a proof is for a single mint, deposit merkle powers and allowlisted
+    // tokens one by one
+    if inst_data.power_proof.is_some() {
+        return Err(ContractError::InvalidPowerProof.into());
+    }
+    if inst_data.allowlist_proof.is_some() {
+        return Err(ContractError::InvalidAllowlistProof.into());
+    }
+    let pool_collections = get_pool_collections(program_id, pool_pda_account, pool_collections_pda)?;
+    let (lock_duration, multiplier) = get_lock_tier(&pool_data, inst_data.lock_tier)?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    if is_pool_ended(&pool_data, now) {
+        return Err(ContractError::PoolEnded.into());
+    }
+    // continuous pools accrue per second and leave the payrolls alone
+    let continuous = is_continuous_pool(&pool_data);
+    let next_payroll_index = match continuous {
+        true => {
+            accrue_emission(&mut pool_data, now)?;
+            get_current_payroll_index(now, pool_data.reward_period, pool_data.start_at) + 1
+        }
+        false => {
+            let (next_payroll, next_payroll_index) = get_or_create_next_payroll_by_time(
+                now,
+                program_id,
+                account,
+                pool_pda_account,
+                payroll_pda,
+                system_program_account,
+                pool_data.clone(),
+            )?;
+            if next_payroll != *payroll_pda.key {
+                return Err(ContractError::InvalidPdaAccount.into());
+            }
+            next_payroll_index
+        }
+    };
+    let lamports_required = Rent::get()?.minimum_balance(STAKING_PDA_LEN);
+    let mut batch_deposited_power: u64 = 0;
+    let mut deposited_count = 0;
+    // six accounts per nft, until the remaining accounts run out
+    while let Ok(staking_token_mint_account) = next_account_info(accounts_iter) {
+        let staking_token_source_associated_account = next_account_info(accounts_iter)?;
+        let staking_token_dest_associated_account = next_account_info(accounts_iter)?;
+        let meta_pda = next_account_info(accounts_iter)?;
+        let staking_token_data_pda = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let metadata = get_nft_metadata(staking_token_mint_account, meta_pda)?;
+        verify_depositable(&pool_data, &pool_collections, &metadata, &None)?;
+        if is_programmable(&metadata.token_standard) {
+            return Err(ContractError::UnsupportedTokenStandard.into());
+        }
+        let (expected_token_data_pda, _bump) = get_token_data_pda(
+            staking_token_mint_account.key,
+            pool_pda_account.key,
+            program_id,
+        );
+        if expected_token_data_pda != *staking_token_data_pda.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        let token_power = get_token_power(
+            program_id,
+            pool_pda_account,
+            staking_token_data_pda,
+            power_rules_pda,
+            &metadata,
+            &None,
+            get_collection_power(&pool_collections, &metadata).unwrap_or(1),
+        )?;
+        let (expected_pda_account, bump) = get_staking_pda(
+            pool_pda_account.key,
+            account.key,
+            staking_token_mint_account.key,
+            program_id,
+        )?;
+        if *pda_account.key != expected_pda_account {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        verify_ata_account(
+            pool_pda_account.key,
+            staking_token_dest_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        // a withdrawn position may still be owed rewards, it has to be closed
+        // before the nft is staked again
+        if pda_account.data_len() > 0 {
+            return Err(ContractError::StakingPositionOpen.into());
+        }
+        let signers_seeds: &[&[u8]; 5] = &[
+            STAKING_SEED,
+            &staking_token_mint_account.key.to_bytes(),
+            &account.key.to_bytes(),
+            &pool_pda_account.key.to_bytes(),
+            &[bump],
+        ];
+        let create_pda_account_ix = system_instruction::create_account(
+            account.key,
+            pda_account.key,
+            lamports_required,
+            STAKING_PDA_LEN.try_into().unwrap(),
+            program_id,
+        );
+        invoke_signed(
+            &create_pda_account_ix,
+            &[
+                account.clone(),
+                pda_account.clone(),
+                system_program_account.clone(),
+            ],
+            &[signers_seeds],
+        )?;
+        if staking_token_dest_associated_account.data_len() == 0 {
+            let create_token_account_ix = spl_instruction::create_associated_token_account(
+                account.key,
+                pool_pda_account.key,
+                staking_token_mint_account.key,
+                token_program_account.key,
+            );
+            invoke(
+                &create_token_account_ix,
+                &[
+                    account.clone(),
+                    staking_token_dest_associated_account.clone(),
+                    pool_pda_account.clone(),
+                    staking_token_mint_account.clone(),
+                    system_program_account.clone(),
+                    token_program_account.clone(),
+                ],
+            )?;
+        }
+        let ix = spl_token::instruction::transfer(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_dest_associated_account.key,
+            account.key,
+            &[],
+            1,
+        )?;
+        if invoke(
+            &ix,
+            &[
+                staking_token_source_associated_account.clone(),
+                staking_token_dest_associated_account.clone(),
+                account.clone(),
+                token_program_account.clone(),
+            ],
+        ).is_err() {
+            return Err(ContractError::TransferError.into());
+        }
+        let deposited_power = token_power
+            .checked_mul(multiplier as u64)
+            .ok_or(ContractError::MathOverflow)?
+            / MULTIPLIER_BASE;
+        let staking_account = StakingAccount {
+            account_type: STAKING_ACCOUNT_TYPE,
+            deposited_power,
+            deposited_at: now,
+            withdrawn_at: 0,
+            withdrawn_reward_amount: 0,
+            first_payroll_index: next_payroll_index,
+            depositor: *account.key,
+            pool_pda_account: *pool_pda_account.key,
+            staking_token_mint_address: *staking_token_mint_account.key,
+            withdrawn_address: *account.key,
+            locked_until: now + lock_duration,
+            multiplier,
+            programmable: false,
+            reward_debt: get_emission_reward(deposited_power, &pool_data)?,
+            accrued_reward: 0,
+            settled_payroll_index: 0,
+        };
+        staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+        batch_deposited_power = batch_deposited_power
+            .checked_add(deposited_power)
+            .ok_or(ContractError::MathOverflow)?;
+        deposited_count += 1;
+    }
+    if deposited_count == 0 {
+        return Err(ContractError::InvalidDepositAmount.into());
+    }
+    // pool and payroll are written once for the whole batch
+    pool_data.total_deposited_power = pool_data
+        .total_deposited_power
+        .checked_add(batch_deposited_power)
+        .ok_or(ContractError::MathOverflow)?;
+    add_pending_power(&mut pool_data, batch_deposited_power, next_payroll_index)?;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    if continuous {
+        return Ok(());
+    }
+    let mut payroll_total_reward = 0;
+    let mut reward_withdrawn_amount = 0;
+    let mut claimed_power = 0;
+    if payroll_pda.data_len() > 0 {
+        let current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+        payroll_total_reward = current_payroll_data.total_reward_amount;
+        reward_withdrawn_amount = current_payroll_data.reward_withdrawn_amount;
+        claimed_power = current_payroll_data.claimed_power;
+    }
+    let mut payroll_account_data = Payroll {
+        account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+        total_deposited_power: 0,
+        reward_withdrawn_amount,
+        total_reward_amount: 0,
+        index: next_payroll_index,
+        start_at: pool_data.start_at,
+        rate_reward: 0,
+        reward_remainder: 0,
+        claimed_power,
+        sealed: false,
+        claimable_after: pool_data.start_at + next_payroll_index * pool_data.reward_period,
+        pool_pda_account: *pool_pda_account.key,
+        creator: *account.key,
+    };
+    update_payroll_reward(
+        &mut payroll_account_data,
+        pool_data.total_deposited_power,
+        payroll_total_reward,
+        get_reward_decimals(&pool_data),
+    )?;
+    payroll_account_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+
+    Ok(())
+}
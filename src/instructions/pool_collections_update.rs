@@ -0,0 +1,105 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    system_instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use std::{
+    convert::TryInto
+};
+use crate::common::{
+    get_pool_collections_pda, verify_pool_creator, verify_program_account, verify_system_account,
+    POOL_COLLECTIONS_ACCOUNT_TYPE,
+};
+use crate::schemas::instructions::pool_collections::PoolCollectionsIns;
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::pool_collections::{
+    get_pool_collections_pda_len,
+    PoolCollections,
+    MAX_POOL_COLLECTIONS,
+    POOL_COLLECTIONS_SEED,
+};
+
+use crate::error::ContractError;
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PoolCollectionsIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let pool_collections_pda = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    let (expected_pool_collections_pda, bump) = get_pool_collections_pda(pool_pda_account.key, program_id);
+    if expected_pool_collections_pda != *pool_collections_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let collections = inst_data.collections;
+    let invalid_collection = collections.len() > MAX_POOL_COLLECTIONS
+        || collections.iter().enumerate().any(|(i, collection_power)| {
+            collection_power.power == 0
+                || collections[..i].iter().any(|other| other.collection == collection_power.collection)
+        });
+    if invalid_collection {
+        return Err(ContractError::InvalidCollection.into());
+    }
+    let data_len = get_pool_collections_pda_len(collections.len());
+    let lamports_required = Rent::get()?.minimum_balance(data_len);
+    if pool_collections_pda.data_len() == 0 {
+        let pool_collections_signer_seeds: &[&[u8]; 3] = &[
+            POOL_COLLECTIONS_SEED,
+            &pool_pda_account.key.to_bytes(),
+            &[bump],
+        ];
+        let create_pda_account_ix = system_instruction::create_account(
+            account.key,
+            pool_collections_pda.key,
+            lamports_required,
+            data_len.try_into().unwrap(),
+            program_id,
+        );
+        invoke_signed(
+            &create_pda_account_ix,
+            &[
+                account.clone(),
+                pool_collections_pda.clone(),
+                system_program_account.clone(),
+            ],
+            &[pool_collections_signer_seeds],
+        )?;
+    } else {
+        verify_program_account(pool_collections_pda, program_id)?;
+        // the account follows the list, rent is topped up or given back
+        let lamports = pool_collections_pda.lamports();
+        if lamports_required > lamports {
+            invoke(
+                &system_instruction::transfer(account.key, pool_collections_pda.key, lamports_required - lamports),
+                &[
+                    account.clone(),
+                    pool_collections_pda.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        } else {
+            **pool_collections_pda.lamports.borrow_mut() = lamports_required;
+            **account.lamports.borrow_mut() += lamports - lamports_required;
+        }
+        pool_collections_pda.realloc(data_len, false)?;
+    }
+    let pool_collections = PoolCollections {
+        account_type: POOL_COLLECTIONS_ACCOUNT_TYPE,
+        pool_pda_account: *pool_pda_account.key,
+        collections,
+    };
+    pool_collections.serialize(&mut &mut pool_collections_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
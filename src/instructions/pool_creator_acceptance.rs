@@ -0,0 +1,30 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use crate::common::verify_program_account;
+use crate::schemas::states::pool::Pool;
+use crate::error::ContractError;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if !account.is_signer
+        || pool_data.pending_creator == Pubkey::default()
+        || pool_data.pending_creator != *account.key
+    {
+        return Err(ContractError::InvalidPoolCreator.into());
+    }
+    pool_data.creator = pool_data.pending_creator;
+    pool_data.pending_creator = Pubkey::default();
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use crate::common::{
+    verify_pool_creator, verify_program_account,
+};
+use crate::schemas::instructions::pool_update::PoolCreatorProposalIns;
+use crate::schemas::states::pool::Pool;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PoolCreatorProposalIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    // proposing the default key cancels a pending proposal
+    pool_data.pending_creator = inst_data.new_creator;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
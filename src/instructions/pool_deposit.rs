@@ -3,6 +3,7 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     program::{ invoke_signed, invoke },
+    program_pack::Pack,
     system_instruction,
     pubkey::Pubkey,
     rent::Rent,
@@ -15,22 +16,33 @@ use std::{
 };
 use crate::{common::{   
     get_or_create_next_payroll_by_time,
-    recalculate_reward_rate,
+    is_pool_ended,
+    get_reward_decimals,
+    update_payroll_reward,
     verify_system_account,
     verify_program_account,
-    get_staking_pda, STAKING_ACCOUNT_TYPE, POOL_PAYROLL_ACCOUNT_TYPE, TOKEN_DATA_ACCOUNT_TYPE,
-}, schemas::states::token_data::TOKEN_DATA_PDA_LEN};
+    verify_token_program,
+    get_pool_pda, get_staking_pda, is_non_custodial_pool, is_programmable, next_programmable_accounts,
+    transfer_programmable_nft, verify_ata_account, STAKING_ACCOUNT_TYPE, POOL_PAYROLL_ACCOUNT_TYPE,
+    MULTIPLIER_BASE, get_lock_tier, get_current_payroll_index, is_continuous_pool, accrue_emission,
+    get_emission_reward, get_token_power, verify_depositable, get_pool_collections, get_collection_power,
+    get_nft_metadata, add_pending_power,
+}};
+use crate::schemas::instructions::pool_deposit::PoolDepositIns;
 
 /// Define the type of state stored in accounts
 use crate::schemas::states::pool::{
     Pool,
+    POOL_SEED,
 };
 use crate::schemas::states::payroll::{
     Payroll,
 };
 
-use mpl_token_metadata::{ID as MPL_PROGRAM_ID, state::TokenMetadataAccount};
-use mpl_token_metadata::state::Metadata;
+use mpl_token_metadata::{
+    ID as MPL_PROGRAM_ID,
+    instructions::{FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts},
+};
 
 use crate::schemas::states::staking_account::{
     StakingAccount,
@@ -39,7 +51,6 @@ use crate::schemas::states::staking_account::{
 };
 
 use crate::schemas::states::token_data::{
-    TokenData,
     TOKEN_DATA_SEED
 };
 
@@ -49,8 +60,8 @@ use spl_associated_token_account::{
 use crate::error::ContractError;
 pub fn process_instruction <'a>(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &'a [AccountInfo<'a>], // The account to say hello to
-    _instruction_data: &[u8],
+    accounts: &[AccountInfo<'a>], // The account to say hello to
+    inst_data: PoolDepositIns,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
@@ -60,6 +71,8 @@ pub fn process_instruction <'a>(
     let staking_token_source_associated_account= next_account_info(accounts_iter)?;
     let staking_token_dest_associated_account = next_account_info(accounts_iter)?;
     let staking_token_data_pda = next_account_info(accounts_iter)?;
+    let power_rules_pda = next_account_info(accounts_iter)?;
+    let pool_collections_pda = next_account_info(accounts_iter)?;
     let payroll_pda = next_account_info(accounts_iter)?;
     let meta_pda = next_account_info(accounts_iter)?;
     let token_program_account = next_account_info(accounts_iter)?;
@@ -68,58 +81,66 @@ pub fn process_instruction <'a>(
     // let pool_pda_account_data = pool_pda_account.data.borrow();
     verify_system_account(account)?;
     verify_program_account(pool_pda_account, program_id)?;
+    verify_token_program(token_program_account)?;
     // verify_program_account(staking_token_data_pda, program_id)?;
     let token_data_seeeds = &[
         TOKEN_DATA_SEED,
         &staking_token_mint_account.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
     ];
-    if *meta_pda.owner != MPL_PROGRAM_ID {
-        return Err(ContractError::InvalidPdaAccount.into());
-    }
-    let metadata = Metadata::from_account_info(meta_pda)?;
+    let metadata = get_nft_metadata(staking_token_mint_account, meta_pda)?;
     let (expected_token_data_pda, _bump) = Pubkey::find_program_address(token_data_seeeds, program_id);
     if expected_token_data_pda != *staking_token_data_pda.key {
         return Err(ContractError::InvalidPdaAccount.into());
     }
-    // let inst_data = PoolDepositIns::try_from_slice(&instruction_data)?;
     let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
-    let collection = metadata.collection.unwrap();
-    if collection.key != pool_data.collection || collection.verified != true {
-        return Err(ContractError::InvalidCollection.into());
+    if pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
     }
+    let pool_collections = get_pool_collections(program_id, pool_pda_account, pool_collections_pda)?;
+    verify_depositable(&pool_data, &pool_collections, &metadata, &inst_data.allowlist_proof)?;
+    let (lock_duration, multiplier) = get_lock_tier(&pool_data, inst_data.lock_tier)?;
     // accept +- 10 seconds differences
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
+    if is_pool_ended(&pool_data, now as u64) {
+        return Err(ContractError::PoolEnded.into());
+    }
     let (expected_pda_account, bump) = get_staking_pda(
-        &pool_pda_account.key,
-        &account.key,
-        &staking_token_mint_account.key,
+        pool_pda_account.key,
+        account.key,
+        staking_token_mint_account.key,
         program_id
     ).ok().unwrap();
-    let token_data: TokenData = match staking_token_data_pda.data_len() != TOKEN_DATA_PDA_LEN {
-        true => TokenData {
-            account_type: TOKEN_DATA_ACCOUNT_TYPE,
-            power: 1,
-            token_mint_address: *staking_token_mint_account.key,
-        },
-        false => TokenData::try_from_slice(&staking_token_data_pda.data.borrow())?
-    };
-    // let token_data = TokenData::try_from_slice(&staking_token_data_pda.data.borrow())?;
-    let (next_payroll, next_payroll_index) = match get_or_create_next_payroll_by_time(
-        now as u64,
+    let token_power = get_token_power(
         program_id,
-        account,
         pool_pda_account,
-        payroll_pda,
-        system_program_account,
-        pool_data.clone(),
-    ) {
-        Ok(p) => p,
-        Err(err) => return Err(err),
+        staking_token_data_pda,
+        power_rules_pda,
+        &metadata,
+        &inst_data.power_proof,
+        get_collection_power(&pool_collections, &metadata).unwrap_or(1),
+    )?;
+    // continuous pools accrue per second and leave the payrolls alone
+    let continuous = is_continuous_pool(&pool_data);
+    let next_payroll_index = match continuous {
+        true => get_current_payroll_index(now as u64, pool_data.reward_period, pool_data.start_at) + 1,
+        false => {
+            let (next_payroll, next_payroll_index) = get_or_create_next_payroll_by_time(
+                now as u64,
+                program_id,
+                account,
+                pool_pda_account,
+                payroll_pda,
+                system_program_account,
+                pool_data.clone(),
+            )?;
+            if next_payroll != *payroll_pda.key {
+                return Err(ContractError::InvalidPdaAccount.into());
+            }
+            next_payroll_index
+        }
     };
-    if next_payroll != *payroll_pda.key {
-        return Err(ContractError::InvalidPdaAccount.into());
-    }
     if *pda_account.key != expected_pda_account {
         return Err(ContractError::InvalidPdaAccount.into());
     }
@@ -135,110 +156,227 @@ pub fn process_instruction <'a>(
         &pool_pda_account.key.to_bytes(),
         &[bump],
     ];
+    // a withdrawn position may still be owed rewards, it has to be closed
+    // before the nft is staked again
     let pda_account_data_len = pda_account.data_len();
-    if pda_account_data_len <= 0 {
-        msg!("Creating or updating pda");
-        let create_pda_account_ix = system_instruction::create_account(
-            &account.key,
-            &pda_account.key,
-            lamports_required,
-            STAKING_PDA_LEN.try_into().unwrap(),
-            &program_id,
-        );
-        
-        invoke_signed(
-            &create_pda_account_ix,
+    if pda_account_data_len > 0 {
+        return Err(ContractError::StakingPositionOpen.into());
+    }
+    msg!("Creating pda");
+    let create_pda_account_ix = system_instruction::create_account(
+        account.key,
+        pda_account.key,
+        lamports_required,
+        STAKING_PDA_LEN.try_into().unwrap(),
+        program_id,
+    );
+    invoke_signed(
+        &create_pda_account_ix,
+        &[
+            account.clone(),
+            pda_account.clone(),
+            system_program_account.clone(),
+        ],
+        &[signers_seeds],
+    )?;
+    let programmable = is_programmable(&metadata.token_standard);
+    if programmable && is_non_custodial_pool(&pool_data) {
+        return Err(ContractError::UnsupportedTokenStandard.into());
+    }
+    if programmable {
+        // token accounts of programmable nfts are frozen by the token metadata
+        // program, only its transfer can move them
+        let edition_account = next_account_info(accounts_iter)?;
+        let token_metadata_program = next_account_info(accounts_iter)?;
+        let programmable_accounts = next_programmable_accounts(accounts_iter)?;
+        transfer_programmable_nft(
+            token_metadata_program,
+            staking_token_source_associated_account,
+            account,
+            staking_token_dest_associated_account,
+            pool_pda_account,
+            staking_token_mint_account,
+            meta_pda,
+            edition_account,
+            account,
+            account,
+            system_program_account,
+            token_program_account,
+            &programmable_accounts,
+            &[],
+        )?;
+    } else if is_non_custodial_pool(&pool_data) {
+        // the nft stays in the depositor wallet, delegated to the pool and frozen
+        let edition_account = next_account_info(accounts_iter)?;
+        let token_metadata_program = next_account_info(accounts_iter)?;
+        if *token_metadata_program.key != MPL_PROGRAM_ID {
+            return Err(ContractError::InvalidProgramAccount.into());
+        }
+        verify_ata_account(
+            account.key,
+            staking_token_source_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        let source_token_account = spl_token::state::Account::unpack(
+            &staking_token_source_associated_account.data.borrow()
+        )?;
+        if source_token_account.amount != 1 || source_token_account.is_frozen() {
+            return Err(ContractError::TransferError.into());
+        }
+        let ix = spl_token::instruction::approve(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            pool_pda_account.key,
+            account.key,
+            &[],
+            1,
+        )?;
+        invoke(
+            &ix,
             &[
+                staking_token_source_associated_account.clone(),
+                pool_pda_account.clone(),
                 account.clone(),
-                pda_account.clone(),
-                system_program_account.clone(),
+                token_program_account.clone(),
             ],
-            &[signers_seeds],
         )?;
-        let create_token_account_ix = spl_instruction::create_associated_token_account(
-            &account.key,
-            &pool_pda_account.key,
-            &staking_token_mint_account.key,
-            // &token_program_account.key
-        );
-        invoke(
-            &create_token_account_ix,
+        let (_, pool_bump) = get_pool_pda(&pool_data.seed_name, &pool_data.seed_creator, program_id);
+        let pool_pda_signers_seeds: &[&[u8]; 4] = &[
+            &pool_data.seed_name[..],
+            POOL_SEED,
+            &pool_data.seed_creator.to_bytes(),
+            &[pool_bump],
+        ];
+        FreezeDelegatedAccountCpi::new(
+            token_metadata_program,
+            FreezeDelegatedAccountCpiAccounts {
+                delegate: pool_pda_account,
+                token_account: staking_token_source_associated_account,
+                edition: edition_account,
+                mint: staking_token_mint_account,
+                token_program: token_program_account,
+            },
+        ).invoke_signed(&[pool_pda_signers_seeds])?;
+    } else {
+        // the nft has to end up in the pool, not in another account of the staker
+        verify_ata_account(
+            pool_pda_account.key,
+            staking_token_dest_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        if staking_token_dest_associated_account.data_len() == 0 {
+            let create_token_account_ix = spl_instruction::create_associated_token_account(
+                account.key,
+                pool_pda_account.key,
+                staking_token_mint_account.key,
+                token_program_account.key,
+            );
+            invoke(
+                &create_token_account_ix,
+                &[
+                  account.clone(),
+                  staking_token_dest_associated_account.clone(),
+                  pool_pda_account.clone(),
+                  staking_token_mint_account.clone(),
+                  system_program_account.clone(),
+                  token_program_account.clone(),
+                ],
+            )?;
+        }
+        // now transfer
+        let ix = spl_token::instruction::transfer(
+            token_program_account.key,
+            staking_token_source_associated_account.key,
+            staking_token_dest_associated_account.key,
+            account.key,
+            &[],
+            1,
+        )?;
+        match invoke(
+            &ix,
             &[
-              account.clone(),
-              staking_token_dest_associated_account.clone(),
-              pool_pda_account.clone(),
-              staking_token_mint_account.clone(),
-              system_program_account.clone(),
-              token_program_account.clone(),
+                staking_token_source_associated_account.clone(),
+                staking_token_dest_associated_account.clone(),
+                account.clone(),
+                token_program_account.clone(),
             ],
-        )?;
-
+        ) {
+            Ok(p) => p,
+            Err(_err) => return Err(ContractError::TransferError.into()),
+        };
+    }
+    let deposited_power = token_power
+        .checked_mul(multiplier as u64)
+        .ok_or(ContractError::MathOverflow)?
+        / MULTIPLIER_BASE;
+    if continuous {
+        accrue_emission(&mut pool_data, deposited_at)?;
     }
-    // now transfer
-    let ix = spl_token::instruction::transfer(
-        &token_program_account.key,
-        &staking_token_source_associated_account.key,
-        &staking_token_dest_associated_account.key,
-        &account.key,
-        &[],
-        1,
-    )?;
-    // let signers_seeds: &[&[u8]; 1] = &[
-    //     &pda_account.key.to_bytes(),
-    // ];
-    match invoke(
-        &ix,
-        &[
-            staking_token_source_associated_account.clone(),
-            staking_token_dest_associated_account.clone(),
-            account.clone(),
-            token_program_account.clone(),
-        ],
-    ) {
-        Ok(p) => p,
-        Err(_err) => return Err(ContractError::TransferError.into()),
-    };
     let staking_account = StakingAccount {
         account_type: STAKING_ACCOUNT_TYPE,
-        deposited_power: token_data.power,
+        deposited_power,
         deposited_at,
         withdrawn_at: 0,
         withdrawn_reward_amount: 0,
         first_payroll_index,
-        depositor: account.key.clone(),
-        pool_pda_account: pool_pda_account.key.clone(),
+        depositor: *account.key,
+        pool_pda_account: *pool_pda_account.key,
         staking_token_mint_address: *staking_token_mint_account.key,
         withdrawn_address,
+        locked_until: deposited_at + lock_duration,
+        multiplier,
+        programmable,
+        // what the pool accrued before the deposit is not owed to it
+        reward_debt: get_emission_reward(deposited_power, &pool_data)?,
+        accrued_reward: 0,
+        settled_payroll_index: 0,
     };
     staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
-    pool_data.total_deposited_power += token_data.power;
+    pool_data.total_deposited_power = pool_data
+        .total_deposited_power
+        .checked_add(deposited_power)
+        .ok_or(ContractError::MathOverflow)?;
+    add_pending_power(&mut pool_data, deposited_power, first_payroll_index)?;
     let reward_period = pool_data.reward_period;
     let start_at = pool_data.start_at;
     let total_deposited_power = pool_data.total_deposited_power;
+    let reward_decimals = get_reward_decimals(&pool_data);
     pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    if continuous {
+        return Ok(());
+    }
     let mut payroll_total_reward: u64 = 0;
     let mut reward_withdrawn_amount = 0;
+    let mut claimed_power = 0;
     if payroll_pda.data_len() > 0 {
         let current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
-        payroll_total_reward += current_payroll_data.total_reward_amount;
+        payroll_total_reward = payroll_total_reward
+            .checked_add(current_payroll_data.total_reward_amount)
+            .ok_or(ContractError::MathOverflow)?;
         reward_withdrawn_amount = current_payroll_data.reward_withdrawn_amount;
+        claimed_power = current_payroll_data.claimed_power;
     }
-    let rate_reward = recalculate_reward_rate(
-        total_deposited_power,
-        payroll_total_reward,
-    );
-    let payroll_account_data = Payroll {
+    let mut payroll_account_data = Payroll {
         account_type: POOL_PAYROLL_ACCOUNT_TYPE,
-        total_deposited_power,
+        total_deposited_power: 0,
         reward_withdrawn_amount,
-        total_reward_amount: payroll_total_reward,
+        total_reward_amount: 0,
         index: next_payroll_index,
         start_at,
-        rate_reward,
+        rate_reward: 0,
+        reward_remainder: 0,
+        claimed_power,
+        sealed: false,
         claimable_after: start_at + next_payroll_index * reward_period,
         pool_pda_account: *pool_pda_account.key,
         creator: *account.key
     };
+    update_payroll_reward(
+        &mut payroll_account_data,
+        total_deposited_power,
+        payroll_total_reward,
+        reward_decimals,
+    )?;
     payroll_account_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
 
     Ok(())
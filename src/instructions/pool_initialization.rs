@@ -18,13 +18,13 @@ use crate::{schemas::states::pool::{
     Pool,
     POOL_PDA_LEN,
     POOL_SEED,
-}, common::POOL_ACCOUNT_TYPE};
+}, common::{verify_reward_token_program, POOL_ACCOUNT_TYPE}};
 use crate::schemas::instructions::pool_initialization::PoolInitializationIns;
 use crate::error::ContractError;
 pub fn process_instruction(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
     accounts: &[AccountInfo], // The account to say hello to
-    instruction_data: &[u8],
+    inst_data: PoolInitializationIns,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
@@ -36,7 +36,8 @@ pub fn process_instruction(
     if account.owner != &SYSTEM_PROGRAM_ID {
         return Err(ContractError::NotASystemAccount.into());
     }
-    let inst_data = PoolInitializationIns::try_from_slice(&instruction_data)?;
+    // the reward ata is created under the token program of the mint
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
     let lamports_required = Rent::get()?.minimum_balance(POOL_PDA_LEN);
     let pool_name = &inst_data.name;
     let account_seeds: &[&[u8]; 3] = &[
@@ -56,11 +57,11 @@ pub fn process_instruction(
         &[bump],
     ];
     let create_pda_account_ix = system_instruction::create_account(
-        &account.key,
-        &pda_account.key,
+        account.key,
+        pda_account.key,
         lamports_required,
         POOL_PDA_LEN.try_into().unwrap(),
-        &program_id,
+        program_id,
     );
     
     invoke_signed(
@@ -74,10 +75,10 @@ pub fn process_instruction(
     )?;
     // create ata for reward
     let create_token_account_ix = spl_instruction::create_associated_token_account(
-        &account.key,
-        &pda_account.key,
-        &reward_token_mint_account.key,
-        // &token_program_account.key
+        account.key,
+        pda_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
     );
     invoke(
         &create_token_account_ix,
@@ -103,6 +104,9 @@ pub fn process_instruction(
     pool_account_data.creator = inst_data.creator;
     pool_account_data.collection = inst_data.collection;
     pool_account_data.pool_type = inst_data.pool_type;
+    pool_account_data.power_authority = inst_data.creator;
+    pool_account_data.seed_name = inst_data.name;
+    pool_account_data.seed_creator = *account.key;
     pool_account_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
     Ok(())
 }
@@ -0,0 +1,38 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+use crate::common::verify_program_account;
+use crate::schemas::instructions::pool_update::PoolPauseIns;
+use crate::schemas::states::pool::Pool;
+use crate::error::ContractError;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PoolPauseIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    let is_guardian = pool_data.guardian != Pubkey::default() && pool_data.guardian == *account.key;
+    if !account.is_signer || (pool_data.creator != *account.key && !is_guardian) {
+        return Err(ContractError::InvalidPoolCreator.into());
+    }
+    msg!(
+        "Pausing deposit: {:?}, reward withdrawal: {:?}, fund withdrawal: {:?}",
+        inst_data.deposit_paused,
+        inst_data.reward_withdrawal_paused,
+        inst_data.fund_withdrawal_paused
+    );
+    pool_data.deposit_paused = inst_data.deposit_paused;
+    pool_data.reward_withdrawal_paused = inst_data.reward_withdrawal_paused;
+    pool_data.fund_withdrawal_paused = inst_data.fund_withdrawal_paused;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
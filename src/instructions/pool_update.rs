@@ -0,0 +1,119 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use crate::common::{
+    get_max_payroll_index, verify_pool_creator, verify_program_account, MAX_REWARD_DECIMALS,
+    MULTIPLIER_BASE,
+};
+use crate::schemas::instructions::pool_update::PoolUpdateIns;
+use crate::schemas::states::pool::Pool;
+use crate::error::ContractError;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PoolUpdateIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    // payroll indexes are derived from start_at and reward_period, once the
+    // pool has started they must stay as they are, as must the precision
+    // payroll rates are computed with
+    let schedule_changed = inst_data.reward_period.is_some()
+        || inst_data.start_at.is_some()
+        || inst_data.pool_type.is_some()
+        || inst_data.reward_decimals.is_some();
+    if schedule_changed {
+        msg!("Checking pool schedule");
+        if now >= pool_data.start_at || pool_data.total_deposited_power > 0 {
+            return Err(ContractError::PoolAlreadyStarted.into());
+        }
+        if let Some(reward_period) = inst_data.reward_period {
+            if reward_period == 0 {
+                return Err(ContractError::InvalidTimeRange.into());
+            }
+            pool_data.reward_period = reward_period;
+        }
+        if let Some(start_at) = inst_data.start_at {
+            if start_at <= now {
+                return Err(ContractError::InvalidTimeRange.into());
+            }
+            pool_data.start_at = start_at;
+        }
+        if let Some(pool_type) = inst_data.pool_type {
+            pool_data.pool_type = pool_type;
+        }
+        if let Some(reward_decimals) = inst_data.reward_decimals {
+            if reward_decimals as u32 > MAX_REWARD_DECIMALS {
+                return Err(ContractError::InvalidRewardDecimals.into());
+            }
+            pool_data.reward_decimals = reward_decimals;
+        }
+    }
+    if let Some(name) = inst_data.name {
+        pool_data.name = name;
+    }
+    if let Some(collection) = inst_data.collection {
+        pool_data.collection = collection;
+    }
+    if let Some(power_authority) = inst_data.power_authority {
+        pool_data.power_authority = power_authority;
+    }
+    if let Some(guardian) = inst_data.guardian {
+        pool_data.guardian = guardian;
+    }
+    if let Some(lock_tiers) = inst_data.lock_tiers {
+        // a lock never lowers the power of a token
+        let invalid_tier = lock_tiers.iter().any(|tier| {
+            tier.duration > 0 && (tier.multiplier as u64) < MULTIPLIER_BASE
+        });
+        if invalid_tier {
+            return Err(ContractError::InvalidLockTier.into());
+        }
+        pool_data.lock_tiers = lock_tiers;
+    }
+    if let Some(allowlist_root) = inst_data.allowlist_root {
+        pool_data.allowlist_root = allowlist_root;
+    }
+    if let Some(claim_expiry) = inst_data.claim_expiry {
+        // stakers keep at least the claim window they staked under
+        let shortened = claim_expiry > 0
+            && (pool_data.claim_expiry == 0 || claim_expiry < pool_data.claim_expiry);
+        if shortened && (now >= pool_data.start_at || pool_data.total_deposited_power > 0) {
+            return Err(ContractError::PoolAlreadyStarted.into());
+        }
+        pool_data.claim_expiry = claim_expiry;
+    }
+    if let Some(crank_tip) = inst_data.crank_tip {
+        pool_data.crank_tip = crank_tip;
+    }
+    if let Some(end_at) = inst_data.end_at {
+        // stakers keep at least the payrolls they staked for
+        let shortened = end_at > 0 && (pool_data.end_at == 0 || end_at < pool_data.end_at);
+        if shortened && (now >= pool_data.start_at || pool_data.total_deposited_power > 0) {
+            return Err(ContractError::PoolAlreadyStarted.into());
+        }
+        if end_at > 0 && end_at <= now {
+            return Err(ContractError::InvalidTimeRange.into());
+        }
+        pool_data.end_at = end_at;
+    }
+    // the schedule may have moved under the end as well
+    if pool_data.end_at > 0 && pool_data.end_at <= pool_data.start_at {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    pool_data.max_payroll_index = get_max_payroll_index(&pool_data);
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
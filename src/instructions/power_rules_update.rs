@@ -0,0 +1,78 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    system_instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use std::{
+    convert::TryInto
+};
+use crate::common::{
+    get_power_rules_pda, verify_power_authority, verify_program_account, verify_system_account,
+    POWER_RULES_ACCOUNT_TYPE,
+};
+use crate::schemas::instructions::power_rules::PowerRulesIns;
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::power_rules::{
+    PowerRules,
+    POWER_RULES_PDA_LEN,
+    POWER_RULES_SEED,
+};
+
+use crate::error::ContractError;
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: PowerRulesIns,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let power_rules_pda = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_power_authority(account, &pool_data)?;
+    let (expected_power_rules_pda, bump) = get_power_rules_pda(pool_pda_account.key, program_id);
+    if expected_power_rules_pda != *power_rules_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    // the rules are replaced as a whole, the account is created on first use
+    if power_rules_pda.data_len() == 0 {
+        let power_rules_signer_seeds: &[&[u8]; 3] = &[
+            POWER_RULES_SEED,
+            &pool_pda_account.key.to_bytes(),
+            &[bump],
+        ];
+        let lamports_required = Rent::get()?.minimum_balance(POWER_RULES_PDA_LEN);
+        let create_pda_account_ix = system_instruction::create_account(
+            account.key,
+            power_rules_pda.key,
+            lamports_required,
+            POWER_RULES_PDA_LEN.try_into().unwrap(),
+            program_id,
+        );
+        invoke_signed(
+            &create_pda_account_ix,
+            &[
+                account.clone(),
+                power_rules_pda.clone(),
+                system_program_account.clone(),
+            ],
+            &[power_rules_signer_seeds],
+        )?;
+    }
+    let power_rules = PowerRules {
+        account_type: POWER_RULES_ACCOUNT_TYPE,
+        pool_pda_account: *pool_pda_account.key,
+        merkle_root: inst_data.merkle_root,
+        creator_powers: inst_data.creator_powers,
+    };
+    power_rules.serialize(&mut &mut power_rules_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use crate::common::{
+    get_current_payroll_index, get_payroll_claimable_after, get_payroll_pda, get_payroll_reward_due,
+    get_rewarder_pda, get_staking_payroll_pda, is_claim_expired, transfer_reward,
+    verify_program_account, verify_reward_ata_account, verify_reward_token_program,
+    verify_system_account, get_reward_decimals, record_payroll_claim,
+    STAKING_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::error::ContractError;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::{Pool, REWADER_SEED};
+use crate::schemas::states::staking_account::StakingAccount;
+use crate::schemas::states::staking_payroll::{
+    StakingPayroll, STAKING_PAYROLL_PDA_LEN, STAKING_PAYROLL_SEED,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+use std::convert::TryInto;
+
+use crate::schemas::instructions::reward_redemption::RewardRangeRedemption;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRangeRedemption,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let dst_account = next_account_info(accounts_iter)?;
+    let reward_token_mint = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    let _associated_token_program = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(pda_account, program_id)?;
+    let mut staking_account = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.reward_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if staking_account.pool_pda_account != *pool_pda_account.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if staking_account.withdrawn_address != *dst_account.key {
+        return Err(ContractError::InvalidWithdrawnAddress.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint.key {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    verify_reward_ata_account(
+        &staking_account.withdrawn_address,
+        reward_token_dest_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    let RewardRangeRedemption { start_index, end_index } = inst_data;
+    let now = Clock::get()?.unix_timestamp as u64;
+    if start_index < staking_account.first_payroll_index
+        || end_index < start_index
+        || now < get_payroll_claimable_after(end_index, &pool_data)
+    {
+        return Err(ContractError::InvalidTimeRange.into());
+    }
+    // fund withdrawn
+    if staking_account.withdrawn_at > 0 {
+        let latest_payroll_index = get_current_payroll_index(
+            staking_account.withdrawn_at,
+            pool_data.reward_period,
+            pool_data.start_at,
+        );
+        if latest_payroll_index.checked_sub(1).ok_or(ContractError::MathOverflow)? < end_index {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+    }
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            &staking_account.withdrawn_address,
+            reward_token_mint.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                reward_token_dest_associated_account.clone(),
+                dst_account.clone(),
+                reward_token_mint.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    let mut total_reward_amount: u64 = 0;
+    for index in start_index..=end_index {
+        let payroll_pda = next_account_info(accounts_iter)?;
+        let staking_payroll_account = next_account_info(accounts_iter)?;
+        let reward_pda = next_account_info(accounts_iter)?;
+        let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+        let (expected_payroll, _bump) = get_payroll_pda(index, pool_pda_account.key, program_id);
+        if expected_payroll != *payroll_pda.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        if is_claim_expired(index, &pool_data, now) {
+            return Err(ContractError::ClaimExpired.into());
+        }
+        // nobody funded this payroll
+        if payroll_pda.data_len() == 0 {
+            continue;
+        }
+        verify_program_account(payroll_pda, program_id)?;
+        let mut payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+        if !payroll_data.sealed {
+            return Err(ContractError::PayrollNotSealed.into());
+        }
+        let (staking_payroll_pda, staking_payroll_bump) =
+            get_staking_payroll_pda(index, pool_pda_account.key, pda_account.key, program_id);
+        if staking_payroll_pda != *staking_payroll_account.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        let mut total_withdrawn_reward = 0;
+        if staking_payroll_account.data_len() > 0 {
+            // staking payroll accounts are allocated larger than the struct
+            let staking_payroll_data =
+                StakingPayroll::deserialize(&mut &staking_payroll_account.data.borrow()[..])?;
+            total_withdrawn_reward = staking_payroll_data.reward_withdrawn_amount;
+        }
+        let reward_amount = get_payroll_reward_due(
+            staking_account.deposited_power,
+            total_withdrawn_reward,
+            &payroll_data,
+            get_reward_decimals(&pool_data),
+        )?;
+        if reward_amount == 0 {
+            continue;
+        }
+        let (expected_rewarder, reward_bump) =
+            get_rewarder_pda(payroll_pda.key, pool_pda_account.key, program_id);
+        if expected_rewarder != *reward_pda.key {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        verify_reward_ata_account(
+            reward_pda.key,
+            reward_token_pool_associated_account.key,
+            reward_token_mint.key,
+            token_program_account.key,
+        )?;
+        let first_claim = staking_payroll_account.data_len() == 0;
+        if first_claim {
+            let parsed_index = index.to_string();
+            let staking_payroll_signers_seeds: &[&[u8]; 5] = &[
+                STAKING_PAYROLL_SEED,
+                parsed_index.as_bytes(),
+                &pool_pda_account.key.to_bytes(),
+                &pda_account.key.to_bytes(),
+                &[staking_payroll_bump],
+            ];
+            let lamports_required = Rent::get()?.minimum_balance(STAKING_PAYROLL_PDA_LEN);
+            let create_staking_payroll_pda_account_ix = system_instruction::create_account(
+                account.key,
+                staking_payroll_account.key,
+                lamports_required,
+                STAKING_PAYROLL_PDA_LEN.try_into().unwrap(),
+                program_id,
+            );
+            invoke_signed(
+                &create_staking_payroll_pda_account_ix,
+                &[
+                    account.clone(),
+                    staking_payroll_account.clone(),
+                    system_program_account.clone(),
+                ],
+                &[staking_payroll_signers_seeds],
+            )?;
+        }
+        // every payroll has its own rewarder vault
+        let rewarder_pda_signer_seeds: &[&[u8]; 4] = &[
+            REWADER_SEED,
+            &payroll_pda.key.to_bytes(),
+            &pool_pda_account.key.to_bytes(),
+            &[reward_bump],
+        ];
+        transfer_reward(
+            token_program_account,
+            reward_token_pool_associated_account,
+            reward_token_mint,
+            reward_token_dest_associated_account,
+            reward_pda,
+            reward_amount,
+            &[rewarder_pda_signer_seeds],
+        )?;
+        payroll_data.reward_withdrawn_amount = payroll_data
+            .reward_withdrawn_amount
+            .checked_add(reward_amount)
+            .ok_or(ContractError::MathOverflow)?;
+        if first_claim {
+            record_payroll_claim(&mut payroll_data, staking_account.deposited_power);
+        }
+        payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+        let updated_staking_payroll_data = StakingPayroll {
+            account_type: STAKING_PAYROLL_ACCOUNT_TYPE,
+            staking_pda_account: *pda_account.key,
+            deposited_power: staking_account.deposited_power,
+            total_pool_deposited_power: payroll_data.total_deposited_power,
+            total_reward_amount: payroll_data.total_reward_amount,
+            reward_withdrawn_amount: total_withdrawn_reward
+                .checked_add(reward_amount)
+                .ok_or(ContractError::MathOverflow)?,
+            index,
+            withdrawn_at: now,
+        };
+        updated_staking_payroll_data
+            .serialize(&mut &mut staking_payroll_account.data.borrow_mut()[..])?;
+        total_reward_amount = total_reward_amount
+            .checked_add(reward_amount)
+            .ok_or(ContractError::MathOverflow)?;
+    }
+    if total_reward_amount == 0 {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    staking_account.withdrawn_reward_amount = staking_account
+        .withdrawn_reward_amount
+        .checked_add(total_reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
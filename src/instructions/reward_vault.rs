@@ -0,0 +1,90 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    system_instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+use std::{
+    convert::TryInto
+};
+use crate::common::{
+    get_reward_vault_pda, verify_pool_creator, verify_program_account, verify_system_account,
+    REWARD_VAULT_ACCOUNT_TYPE,
+};
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::reward_vault::{
+    RewardVault,
+    REWARD_VAULT_PDA_LEN,
+    REWARD_VAULT_SEED,
+};
+
+use crate::error::ContractError;
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let reward_token_mint_account = next_account_info(accounts_iter)?;
+    let reward_vault_pda = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    // the pool reward token keeps using the payroll amounts
+    // legacy and Token-2022 mints, the latter with any extension
+    if pool_data.reward_token_mint_address == *reward_token_mint_account.key
+        || (*reward_token_mint_account.owner != spl_token::id()
+            && *reward_token_mint_account.owner != spl_token_2022::id())
+        || StateWithExtensions::<Mint>::unpack(&reward_token_mint_account.data.borrow()).is_err()
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    let (expected_reward_vault, bump) = get_reward_vault_pda(
+        pool_pda_account.key,
+        reward_token_mint_account.key,
+        program_id,
+    );
+    if expected_reward_vault != *reward_vault_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let reward_vault_signer_seeds: &[&[u8]; 4] = &[
+        REWARD_VAULT_SEED,
+        &pool_pda_account.key.to_bytes(),
+        &reward_token_mint_account.key.to_bytes(),
+        &[bump],
+    ];
+    let lamports_required = Rent::get()?.minimum_balance(REWARD_VAULT_PDA_LEN);
+    let create_pda_account_ix = system_instruction::create_account(
+        account.key,
+        reward_vault_pda.key,
+        lamports_required,
+        REWARD_VAULT_PDA_LEN.try_into().unwrap(),
+        program_id,
+    );
+    invoke_signed(
+        &create_pda_account_ix,
+        &[
+            account.clone(),
+            reward_vault_pda.clone(),
+            system_program_account.clone(),
+        ],
+        &[reward_vault_signer_seeds],
+    )?;
+    let reward_vault = RewardVault {
+        account_type: REWARD_VAULT_ACCOUNT_TYPE,
+        pool_pda_account: *pool_pda_account.key,
+        reward_token_mint_address: *reward_token_mint_account.key,
+        total_reward_amount: 0,
+        reward_withdrawn_amount: 0,
+    };
+    reward_vault.serialize(&mut &mut reward_vault_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::common::{
-    get_current_payroll_index,
-    get_or_create_payroll_by_index, verify_ata_account, verify_program_account,
-    verify_system_account, DECIMAL_REWARD, STAKING_PAYROLL_ACCOUNT_TYPE,
+    get_current_payroll_index, get_payroll_claimable_after, get_payroll_reward_due,
+    get_or_create_payroll_by_index, is_claim_expired, transfer_reward, verify_program_account,
+    verify_reward_ata_account, verify_reward_token_program, verify_system_account,
+    get_reward_decimals, record_payroll_claim, STAKING_PAYROLL_ACCOUNT_TYPE,
 };
 use crate::error::ContractError;
 use crate::schemas::states::payroll::Payroll;
@@ -21,7 +22,6 @@ use solana_program::{
     rent::Rent,
     system_instruction,
     sysvar::Sysvar,
-    msg,
 };
 use spl_associated_token_account::instruction as spl_instruction;
 use std::convert::TryInto;
@@ -31,8 +31,8 @@ use crate::schemas::instructions::reward_redemption::RewardRedemption;
 // Program entrypoint's implementation
 pub fn process_instruction<'a>(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &'a [AccountInfo<'a>],
-    instruction_data: &[u8],
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRedemption,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
@@ -48,27 +48,35 @@ pub fn process_instruction<'a>(
     let token_program_account = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
     // check for account
-    if payroll_pda.data_len() <= 0 {
+    if payroll_pda.data_len() == 0 {
         return Err(ContractError::NoRewardPayroll.into());
     }
-    let inst_data = RewardRedemption::try_from_slice(instruction_data)?;
     let mut payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
     let mut staking_account = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
-    verify_system_account(&account)?;
+    verify_system_account(account)?;
     verify_program_account(pool_pda_account, program_id)?;
     verify_program_account(pda_account, program_id)?;
     let clock = Clock::get()?;
     let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    if pool_data.reward_withdrawal_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    if staking_account.pool_pda_account != *pool_pda_account.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
     // only check if dao is not system program
-    verify_ata_account(
-        &reward_pda.key,
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    verify_reward_ata_account(
+        reward_pda.key,
         reward_token_pool_associated_account.key,
         &pool_data.reward_token_mint_address,
+        token_program_account.key,
     )?;
-    verify_ata_account(
+    verify_reward_ata_account(
         &staking_account.withdrawn_address,
         reward_token_dest_associated_account.key,
         &pool_data.reward_token_mint_address,
+        token_program_account.key,
     )?;
     if staking_account.withdrawn_address != *dst_account.key {
         return Err(ContractError::InvalidWithdrawnAddress.into());
@@ -114,12 +122,12 @@ pub fn process_instruction<'a>(
     ];
     let ata_account_data_len = reward_token_dest_associated_account.data_len();
     // msg!("ata dst address: {:?}, {:?}" ,staking_account.withdrawn_address, dst_account.key);
-    if ata_account_data_len <= 0 {
+    if ata_account_data_len == 0 {
         let create_token_account_ix = spl_instruction::create_associated_token_account(
-            &account.key,
+            account.key,
             &staking_account.withdrawn_address,
             &pool_data.reward_token_mint_address,
-            // &token_program_account.key,
+            token_program_account.key,
         );
         invoke(
             &create_token_account_ix,
@@ -143,7 +151,7 @@ pub fn process_instruction<'a>(
             pool_data.reward_period,
             pool_data.start_at,
         );
-        if latest_payroll_index - 1 < payroll_data.index {
+        if latest_payroll_index.checked_sub(1).ok_or(ContractError::MathOverflow)? < payroll_data.index {
             return Err(ContractError::InvalidPdaAccount.into());
         }
     }
@@ -177,11 +185,11 @@ pub fn process_instruction<'a>(
     ];
     let lamports_required = Rent::get()?.minimum_balance(STAKING_PAYROLL_PDA_LEN);
     let create_staking_payroll_pda_account_ix = system_instruction::create_account(
-        &account.key,
-        &staking_payroll_account.key,
+        account.key,
+        staking_payroll_account.key,
         lamports_required,
         STAKING_PAYROLL_PDA_LEN.try_into().unwrap(),
-        &program_id,
+        program_id,
     );
     invoke_signed(
         &create_staking_payroll_pda_account_ix,
@@ -192,44 +200,48 @@ pub fn process_instruction<'a>(
         ],
         &[staking_payroll_signers_seeds],
     )?;
-    msg!("{:?}", now);
-    msg!("{:?}", payroll_data.claimable_after);
-    if now < payroll_data.claimable_after {
+    // follow the pool schedule, it may have moved since the payroll was funded
+    let claimable_after = get_payroll_claimable_after(payroll_data.index, &pool_data);
+    if now < claimable_after {
         return Err(ContractError::InvalidTimeRange.into());
     }
-    let reward_amount = std::cmp::max(
-        staking_account.deposited_power * payroll_data.rate_reward
-            / u64::pow(10, DECIMAL_REWARD)
-            - total_withdrawn_reward,
-        0,
-    );
+    if is_claim_expired(payroll_data.index, &pool_data, now) {
+        return Err(ContractError::ClaimExpired.into());
+    }
+    // the rate is only final once the payroll is sealed
+    if !payroll_data.sealed {
+        return Err(ContractError::PayrollNotSealed.into());
+    }
+    let reward_amount = get_payroll_reward_due(
+        staking_account.deposited_power,
+        total_withdrawn_reward,
+        &payroll_data,
+        get_reward_decimals(&pool_data),
+    )?;
     if reward_amount == 0 {
         return Err(ContractError::RewardAlreadyWithdrawn.into());
     }
-    payroll_data.reward_withdrawn_amount += reward_amount;
+    payroll_data.reward_withdrawn_amount = payroll_data
+        .reward_withdrawn_amount
+        .checked_add(reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
+    // the staking payroll above is only ever created once, on the first claim
+    record_payroll_claim(&mut payroll_data, staking_account.deposited_power);
     payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
-    // tranfer the interest
-    let ix = spl_token::instruction::transfer(
-        &token_program_account.key,
-        &reward_token_pool_associated_account.key,
-        &reward_token_dest_associated_account.key,
-        &reward_pda.key,
-        &[],
+    // tranfer the interest, the amount that left the rewarder is tracked
+    transfer_reward(
+        token_program_account,
+        reward_token_pool_associated_account,
+        reward_token_mint,
+        reward_token_dest_associated_account,
+        reward_pda,
         reward_amount,
-    )?;
-
-    // msg!("src: {:?}, dest: {:?}, pda: {:?}, token_program: {:?}", &reward_token_pool_associated_account.key, &reward_token_dest_associated_account.key, &pda_account.key, &token_program_account.key);
-    invoke_signed(
-        &ix,
-        &[
-            reward_token_pool_associated_account.clone(),
-            reward_token_dest_associated_account.clone(),
-            reward_pda.clone(),
-            token_program_account.clone(),
-        ],
         &[rewarder_pda_signer_seeds],
     )?;
-    staking_account.withdrawn_reward_amount += reward_amount;
+    staking_account.withdrawn_reward_amount = staking_account
+        .withdrawn_reward_amount
+        .checked_add(reward_amount)
+        .ok_or(ContractError::MathOverflow)?;
     // tranfer the interest
     staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
     let updated_staking_payroll_data = StakingPayroll {
@@ -1,14 +1,17 @@
 use crate::common::{
-    get_or_create_payroll_by_index,
-    recalculate_reward_rate, verify_ata_account, verify_system_account, POOL_PAYROLL_ACCOUNT_TYPE,
+    add_payroll_reward, get_or_create_payroll_by_index, get_payroll_pda, is_continuous_pool, is_past_pool_end,
+    verify_program_account, transfer_reward, verify_reward_ata_account,
+    verify_reward_token_program, verify_system_account,
 };
 use crate::schemas::states::pool::{Pool, REWADER_SEED};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     program::invoke,
     pubkey::Pubkey,
+    sysvar::Sysvar,
 };
 use spl_associated_token_account::{
     instruction as spl_instruction,
@@ -20,8 +23,8 @@ use crate::schemas::instructions::reward_addition::RewardAddition;
 use crate::error::ContractError;
 pub fn process_instruction<'a>(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &'a [AccountInfo<'a>], // The account to say hello to
-    instruction_data: &[u8],
+    accounts: &[AccountInfo<'a>], // The account to say hello to
+    inst_data: RewardAddition,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
@@ -35,10 +38,13 @@ pub fn process_instruction<'a>(
     let system_program_account = next_account_info(accounts_iter)?;
 
     verify_system_account(account)?;
-    verify_ata_account(
-        &account.key,
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_reward_token_program(token_program_account, reward_token_mint_account)?;
+    verify_reward_ata_account(
+        account.key,
         reward_token_source_associated_account.key,
-        &reward_token_mint_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
     )?;
     let rewarder_pda_account_seeds: &[&[u8]; 3] =
         &[
@@ -49,42 +55,58 @@ pub fn process_instruction<'a>(
         ];
     let (expected_rewarder, _bump) =
         Pubkey::find_program_address(rewarder_pda_account_seeds, program_id);
-    verify_ata_account(
+    if expected_rewarder != *rewarder_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    verify_reward_ata_account(
         &expected_rewarder,
         reward_token_dest_associated_account.key,
-        &reward_token_mint_account.key,
+        reward_token_mint_account.key,
+        token_program_account.key,
     )?;
-    let inst_data = RewardAddition::try_from_slice(instruction_data)?;
     let current_payroll_index = inst_data.payroll_index;
     let updated_pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
-    let reward_period = updated_pool_data.reward_period;
-    let start_at = updated_pool_data.start_at;
-    let total_deposited_power = updated_pool_data.total_deposited_power;
+    if updated_pool_data.deposit_paused {
+        return Err(ContractError::PoolPaused.into());
+    }
+    // continuous pools are funded with FundEmission
+    if is_continuous_pool(&updated_pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if is_past_pool_end(current_payroll_index, &updated_pool_data) {
+        return Err(ContractError::PayrollPastPoolEnd.into());
+    }
     let match_token =
         updated_pool_data.reward_token_mint_address == *reward_token_mint_account.key;
     if !match_token {
         return Err(ContractError::InvalidRewardToken.into());
     }
-    if payroll_pda.data_len() <= 0 {
-        let (current_payroll_pda, _currrent_payroll_index) = match get_or_create_payroll_by_index(
+    let (expected_payroll, _bump) = get_payroll_pda(current_payroll_index, pool_pda_account.key, program_id);
+    if expected_payroll != *payroll_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if payroll_pda.data_len() == 0 {
+        get_or_create_payroll_by_index(
             current_payroll_index,
             program_id,
             account,
             pool_pda_account,
             payroll_pda,
             system_program_account,
-        ) {
-            Ok(p) => p,
-            Err(err) => return Err(err),
-        };
-        if current_payroll_pda != *payroll_pda.key {
-            return Err(ContractError::InvalidPdaAccount.into());
+        )?;
+    } else {
+        verify_program_account(payroll_pda, program_id)?;
+        if Payroll::try_from_slice(&payroll_pda.data.borrow())?.sealed {
+            return Err(ContractError::PayrollSealed.into());
         }
+    }
+    // the payroll may already exist from a deposit, without a rewarder ata
+    if reward_token_dest_associated_account.data_len() == 0 {
         let create_token_account_ix = spl_instruction::create_associated_token_account(
-            &account.key,
-            &rewarder_pda.key,
-            &reward_token_mint_account.key,
-            // &token_program_account.key
+            account.key,
+            rewarder_pda.key,
+            reward_token_mint_account.key,
+            token_program_account.key,
         );
         invoke(
             &create_token_account_ix,
@@ -98,55 +120,27 @@ pub fn process_instruction<'a>(
             ],
         )?; 
     }
-    let amount = inst_data.amount;
-
-    let ix = spl_token::instruction::transfer(
-        &token_program_account.key,
-        &reward_token_source_associated_account.key,
-        &reward_token_dest_associated_account.key,
-        &account.key,
+    // transfer fees are withheld, only what the rewarder received is paid out
+    let amount = transfer_reward(
+        token_program_account,
+        reward_token_source_associated_account,
+        reward_token_mint_account,
+        reward_token_dest_associated_account,
+        account,
+        inst_data.amount,
         &[],
-        amount,
-    )?;
-    // let signers_seeds: &[&[u8]; 1] = &[
-    //     &pda_account.key.to_bytes(),
-    // ];
-    invoke(
-        &ix,
-        &[
-            reward_token_source_associated_account.clone(),
-            reward_token_dest_associated_account.clone(),
-            account.clone(),
-            token_program_account.clone(),
-        ],
     )?;
 
-    // update pool's reward info
-    let mut payroll_total_reward: u64 = amount;
-    let mut reward_withdrawn_amount = 0;
-    if payroll_pda.data_len() > 0 {
-        let current_payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
-        payroll_total_reward += current_payroll_data.total_reward_amount;
-        reward_withdrawn_amount = current_payroll_data.reward_withdrawn_amount;
-    }
-    // update pay roll reward's info
-    let rate_reward = recalculate_reward_rate(
-        total_deposited_power,
-        payroll_total_reward,
-    );
-    let payroll_account_data = Payroll {
-        account_type: POOL_PAYROLL_ACCOUNT_TYPE,
-        total_deposited_power,
-        total_reward_amount: payroll_total_reward,
-        rate_reward,
-        reward_withdrawn_amount,
-        index: current_payroll_index,
-        start_at,
-        claimable_after: start_at + current_payroll_index * reward_period,
-        pool_pda_account: *pool_pda_account.key,
-        creator: *account.key,
-    };
-    payroll_account_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    add_payroll_reward(
+        payroll_pda,
+        pool_pda_account,
+        account.key,
+        &updated_pool_data,
+        current_payroll_index,
+        amount,
+        now,
+    )?;
 
     Ok(())
 }
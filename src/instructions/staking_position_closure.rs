@@ -0,0 +1,164 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use crate::common::{
+    close_program_account, get_current_payroll_index, get_payroll_pda, get_pool_pda,
+    get_staking_payroll_pda, is_continuous_pool, record_payroll_claim,
+    verify_ata_account, verify_program_account, verify_system_account, verify_token_program,
+    STAKING_PAYROLL_ACCOUNT_TYPE,
+};
+use crate::schemas::instructions::reward_redemption::RewardRedemption;
+use crate::schemas::states::payroll::Payroll;
+use crate::schemas::states::pool::{Pool, POOL_SEED};
+use crate::schemas::states::staking_account::StakingAccount;
+use crate::schemas::states::staking_payroll::StakingPayroll;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst_data: RewardRedemption,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pda_account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let staking_token_mint_account = next_account_info(accounts_iter)?;
+    let staking_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(pda_account, program_id)?;
+    verify_token_program(token_program_account)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    let mut staking_account = StakingAccount::try_from_slice(&pda_account.data.borrow())?;
+    if !account.is_signer || *account.key != staking_account.depositor {
+        return Err(ContractError::InvalidDepositor.into());
+    }
+    if staking_account.pool_pda_account != *pool_pda_account.key
+        || staking_account.staking_token_mint_address != *staking_token_mint_account.key
+    {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    if staking_account.withdrawn_at == 0 {
+        return Err(ContractError::FundNotWithdrawn.into());
+    }
+    // emission settled at the withdrawal of a continuous stake
+    if staking_account.accrued_reward > 0 {
+        return Err(ContractError::RewardNotClaimed.into());
+    }
+    // the pool token account of custodial stakes, programmable nfts keep
+    // theirs frozen by the token metadata program. Closed before the program
+    // accounts so their lamports only move after the cpi.
+    if staking_token_pool_associated_account.data_len() > 0 {
+        verify_ata_account(
+            pool_pda_account.key,
+            staking_token_pool_associated_account.key,
+            staking_token_mint_account.key,
+        )?;
+        let pool_token_account = spl_token::state::Account::unpack(
+            &staking_token_pool_associated_account.data.borrow()
+        )?;
+        if pool_token_account.amount == 0 && !pool_token_account.is_frozen() {
+            let (_, bump) = get_pool_pda(&pool_data.seed_name, &pool_data.seed_creator, program_id);
+            let pool_pda_signers_seeds: &[&[u8]; 4] = &[
+                &pool_data.seed_name[..],
+                POOL_SEED,
+                &pool_data.seed_creator.to_bytes(),
+                &[bump],
+            ];
+            let ix = spl_token::instruction::close_account(
+                token_program_account.key,
+                staking_token_pool_associated_account.key,
+                account.key,
+                pool_pda_account.key,
+                &[],
+            )?;
+            invoke_signed(
+                &ix,
+                &[
+                    staking_token_pool_associated_account.clone(),
+                    account.clone(),
+                    pool_pda_account.clone(),
+                    token_program_account.clone(),
+                ],
+                &[pool_pda_signers_seeds],
+            )?;
+        }
+    }
+    // every payroll the stake earned from is claimable once the nft is
+    // withdrawn, anything left unclaimed is forfeited with the position. The
+    // forfeited power counts as claimed, so what it leaves in the payroll can
+    // still be rolled over. Payrolls are settled in pages, the cursor keeps
+    // the last one settled until the position is closed.
+    let mut settled = true;
+    if !is_continuous_pool(&pool_data) && staking_account.withdrawn_at >= pool_data.start_at {
+        let last_payroll_index = get_current_payroll_index(
+            staking_account.withdrawn_at,
+            pool_data.reward_period,
+            pool_data.start_at,
+        ) - 1;
+        let start_index = staking_account.first_payroll_index
+            .max(staking_account.settled_payroll_index + 1);
+        let end_index = inst_data.index.min(last_payroll_index);
+        for index in start_index..=end_index {
+            let payroll_pda = next_account_info(accounts_iter)?;
+            let staking_payroll_account = next_account_info(accounts_iter)?;
+            let (expected_payroll, _) = get_payroll_pda(index, pool_pda_account.key, program_id);
+            let (expected_staking_payroll, _) =
+                get_staking_payroll_pda(index, pool_pda_account.key, pda_account.key, program_id);
+            if expected_payroll != *payroll_pda.key
+                || expected_staking_payroll != *staking_payroll_account.key
+            {
+                return Err(ContractError::InvalidPdaAccount.into());
+            }
+            // claimed already, the claim counted the power of the stake
+            if staking_payroll_account.data_len() > 0 {
+                verify_program_account(staking_payroll_account, program_id)?;
+                close_program_account(staking_payroll_account, account)?;
+                continue;
+            }
+            // nobody funded this payroll
+            if payroll_pda.data_len() == 0 {
+                continue;
+            }
+            verify_program_account(payroll_pda, program_id)?;
+            let mut payroll_data = Payroll::try_from_slice(&payroll_pda.data.borrow())?;
+            if !payroll_data.sealed {
+                return Err(ContractError::PayrollNotSealed.into());
+            }
+            record_payroll_claim(&mut payroll_data, staking_account.deposited_power);
+            payroll_data.serialize(&mut &mut payroll_pda.data.borrow_mut()[..])?;
+        }
+        if end_index < last_payroll_index {
+            settled = false;
+            staking_account.settled_payroll_index = staking_account.settled_payroll_index.max(end_index);
+        }
+    }
+    // staking payrolls of registered mints follow. Nothing is recorded for
+    // them, mint payrolls have no claimed power nor rollover: a forfeited
+    // share stays with the rewarder until SweepExpiredMintReward.
+    for staking_payroll_account in accounts_iter {
+        verify_program_account(staking_payroll_account, program_id)?;
+        // staking payroll accounts are allocated larger than the struct
+        let staking_payroll_data =
+            StakingPayroll::deserialize(&mut &staking_payroll_account.data.borrow()[..])?;
+        if staking_payroll_data.account_type != STAKING_PAYROLL_ACCOUNT_TYPE
+            || staking_payroll_data.staking_pda_account != *pda_account.key
+        {
+            return Err(ContractError::InvalidPdaAccount.into());
+        }
+        close_program_account(staking_payroll_account, account)?;
+    }
+    if !settled {
+        staking_account.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+        return Ok(());
+    }
+    close_program_account(pda_account, account)?;
+    Ok(())
+}
@@ -13,8 +13,9 @@ use std::{
     convert::TryInto
 };
 use crate::common::{
-    verify_system_account, TOKEN_DATA_ACCOUNT_TYPE,
+    verify_power_authority, verify_program_account, verify_system_account, TOKEN_DATA_ACCOUNT_TYPE,
 };
+use crate::schemas::states::pool::Pool;
 
 /// Define the type of state stored in accounts
 use crate::schemas::instructions::{
@@ -31,22 +32,26 @@ use crate::schemas::states::token_data::{
 use crate::error::ContractError;
 pub fn process_instruction <'a>(
     program_id: &Pubkey, // Public key of the account the hello world program was loaded into
-    accounts: &'a [AccountInfo<'a>], // The account to say hello to
-    instruction_data: &[u8],
+    accounts: &[AccountInfo<'a>], // The account to say hello to
+    inst: token_data::TokenDataUpdate,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
     let staking_token_mint_account= next_account_info(accounts_iter)?;
     let staking_token_data_pda = next_account_info(accounts_iter)?;
     let system_program_account = next_account_info(accounts_iter)?;
     // check for account
     // let pool_pda_account_data = pool_pda_account.data.borrow();
     msg!("Verifying accounts");
-    let inst = token_data::TokenDataUpdate::try_from_slice(instruction_data)?;
     verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_power_authority(account, &pool_data)?;
     let token_data_seeeds = &[
         TOKEN_DATA_SEED,
         &staking_token_mint_account.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
     ];
     let (expected_token_data_pda, bump) = Pubkey::find_program_address(token_data_seeeds, program_id);
     if expected_token_data_pda != *staking_token_data_pda.key {
@@ -55,15 +60,16 @@ pub fn process_instruction <'a>(
     let token_data_signer_seeeds = &[
         TOKEN_DATA_SEED,
         &staking_token_mint_account.key.to_bytes(),
+        &pool_pda_account.key.to_bytes(),
         &[bump]
     ];
     let lamports_required = Rent::get()?.minimum_balance(TOKEN_DATA_PDA_LEN);
     let create_pda_account_ix = system_instruction::create_account(
-        &account.key,
-        &staking_token_data_pda.key,
+        account.key,
+        staking_token_data_pda.key,
         lamports_required,
         TOKEN_DATA_PDA_LEN.try_into().unwrap(),
-        &program_id,
+        program_id,
     );
     
     invoke_signed(
@@ -79,6 +85,7 @@ pub fn process_instruction <'a>(
         account_type: TOKEN_DATA_ACCOUNT_TYPE,
         power: inst.token_power,
         token_mint_address: *staking_token_mint_account.key,
+        pool_pda_account: *pool_pda_account.key,
     };
     token_data.serialize(&mut &mut staking_token_data_pda.data.borrow_mut()[..])?;
     Ok(())
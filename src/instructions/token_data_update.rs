@@ -0,0 +1,43 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    msg,
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use crate::common::{
+    get_token_data_pda, verify_power_authority, verify_program_account,
+};
+use crate::schemas::instructions::token_data::TokenDataUpdate;
+use crate::schemas::states::pool::Pool;
+use crate::schemas::states::token_data::TokenData;
+use crate::error::ContractError;
+
+pub fn process_instruction <'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    inst: TokenDataUpdate,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let staking_token_mint_account = next_account_info(accounts_iter)?;
+    let staking_token_data_pda = next_account_info(accounts_iter)?;
+    msg!("Verifying accounts");
+    verify_program_account(pool_pda_account, program_id)?;
+    verify_program_account(staking_token_data_pda, program_id)?;
+    let pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_power_authority(account, &pool_data)?;
+    let (expected_token_data_pda, _bump) = get_token_data_pda(
+        staking_token_mint_account.key,
+        pool_pda_account.key,
+        program_id,
+    );
+    if expected_token_data_pda != *staking_token_data_pda.key {
+        return Err(ContractError::InvalidPdaAccount.into());
+    }
+    let mut token_data = TokenData::try_from_slice(&staking_token_data_pda.data.borrow())?;
+    token_data.power = inst.token_power;
+    token_data.serialize(&mut &mut staking_token_data_pda.data.borrow_mut()[..])?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::common::{
+    accrue_emission, get_pool_pda, is_continuous_pool, transfer_reward, verify_pool_creator,
+    verify_program_account, verify_reward_ata_account, verify_reward_token_program,
+    verify_system_account,
+};
+use crate::error::ContractError;
+use crate::schemas::states::pool::{Pool, POOL_SEED};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account::instruction as spl_instruction;
+
+pub fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let pool_pda_account = next_account_info(accounts_iter)?;
+    let reward_token_mint = next_account_info(accounts_iter)?;
+    let reward_token_pool_associated_account = next_account_info(accounts_iter)?;
+    let reward_token_dest_associated_account = next_account_info(accounts_iter)?;
+    let token_program_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+    verify_system_account(account)?;
+    verify_program_account(pool_pda_account, program_id)?;
+    let mut pool_data = Pool::try_from_slice(&pool_pda_account.data.borrow())?;
+    verify_pool_creator(account, &pool_data)?;
+    if !is_continuous_pool(&pool_data) {
+        return Err(ContractError::UnsupportedPoolType.into());
+    }
+    if pool_data.reward_token_mint_address != *reward_token_mint.key
+        || pool_data.reward_ata != *reward_token_pool_associated_account.key
+    {
+        return Err(ContractError::InvalidRewardToken.into());
+    }
+    verify_reward_token_program(token_program_account, reward_token_mint)?;
+    verify_reward_ata_account(
+        account.key,
+        reward_token_dest_associated_account.key,
+        reward_token_mint.key,
+        token_program_account.key,
+    )?;
+    let now = Clock::get()?.unix_timestamp as u64;
+    accrue_emission(&mut pool_data, now)?;
+    let amount = pool_data.undistributed_emission;
+    if amount == 0 {
+        return Err(ContractError::RewardAlreadyWithdrawn.into());
+    }
+    if reward_token_dest_associated_account.data_len() == 0 {
+        let create_token_account_ix = spl_instruction::create_associated_token_account(
+            account.key,
+            account.key,
+            reward_token_mint.key,
+            token_program_account.key,
+        );
+        invoke(
+            &create_token_account_ix,
+            &[
+                account.clone(),
+                reward_token_dest_associated_account.clone(),
+                reward_token_mint.clone(),
+                system_program_account.clone(),
+                token_program_account.clone(),
+            ],
+        )?;
+    }
+    let (_, bump) = get_pool_pda(&pool_data.seed_name, &pool_data.seed_creator, program_id);
+    let pool_pda_signers_seeds: &[&[u8]; 4] = &[
+        &pool_data.seed_name[..],
+        POOL_SEED,
+        &pool_data.seed_creator.to_bytes(),
+        &[bump],
+    ];
+    transfer_reward(
+        token_program_account,
+        reward_token_pool_associated_account,
+        reward_token_mint,
+        reward_token_dest_associated_account,
+        pool_pda_account,
+        amount,
+        &[pool_pda_signers_seeds],
+    )?;
+    pool_data.undistributed_emission = 0;
+    pool_data.serialize(&mut &mut pool_pda_account.data.borrow_mut()[..])?;
+    Ok(())
+}
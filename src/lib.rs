@@ -5,53 +5,172 @@ use solana_program::{
     account_info::AccountInfo,
     pubkey::Pubkey,
 };
+use borsh::BorshDeserialize;
+use crate::instruction::StakingInstruction;
 pub mod instructions;
+pub mod instruction;
 pub mod schemas;
 pub mod common;
 pub mod error;
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    accounts: &[AccountInfo<'a>],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (first, rest) = instruction_data
-        .split_first()
-        .ok_or(ProgramError::InvalidInstructionData)?;
-    match first {
-        1 =>  instructions::pool_initialization::process_instruction(
-                program_id,
-                accounts,
-                rest,
-            ),
-        2 =>  instructions::rewarder_addition::process_instruction(
+    let instruction = StakingInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    match instruction {
+        StakingInstruction::InitializePool(args) => instructions::pool_initialization::process_instruction(
             program_id,
             accounts,
-            rest,
+            args,
         ),
-        3 =>  instructions::pool_deposit::process_instruction(
+        StakingInstruction::AddReward(args) => instructions::rewarder_addition::process_instruction(
             program_id,
             accounts,
-            rest,
+            args,
         ),
-        4 =>  instructions::reward_withdrawal::process_instruction(
+        StakingInstruction::Deposit(args) => instructions::pool_deposit::process_instruction(
             program_id,
             accounts,
-            rest,
+            args,
         ),
-        5 =>  instructions::fund_withdrawal::process_instruction(
+        StakingInstruction::WithdrawReward(args) => instructions::reward_withdrawal::process_instruction(
             program_id,
             accounts,
-            rest,
+            args,
         ),
-        6 =>  instructions::token_data::process_instruction(
+        StakingInstruction::WithdrawFund => instructions::fund_withdrawal::process_instruction(
             program_id,
             accounts,
-            rest,
         ),
-        _ => Err(ProgramError::InvalidInstructionData)
+        StakingInstruction::SetTokenData(args) => instructions::token_data::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::UpdateTokenData(args) => instructions::token_data_update::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::CloseTokenData => instructions::token_data_closure::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::UpdatePool(args) => instructions::pool_update::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::ProposePoolCreator(args) => instructions::pool_creator_proposal::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::AcceptPoolCreator => instructions::pool_creator_acceptance::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::SetPoolPause(args) => instructions::pool_pause::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::AddRewardVault => instructions::reward_vault::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::AddMintReward(args) => instructions::mint_reward_addition::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::WithdrawMintReward(args) => instructions::mint_reward_withdrawal::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::CloseStakingPosition(inst_data) => instructions::staking_position_closure::process_instruction(
+            program_id,
+            accounts,
+            inst_data,
+        ),
+        StakingInstruction::ClaimRange(args) => instructions::reward_range_withdrawal::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::BatchDeposit(args) => instructions::pool_batch_deposit::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::BatchWithdrawFund => instructions::fund_batch_withdrawal::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::FundEmission(args) => instructions::emission_funding::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::ClaimEmission => instructions::emission_withdrawal::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::SetPowerRules(args) => instructions::power_rules_update::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::SetPoolCollections(args) => instructions::pool_collections_update::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::RolloverPayroll(args) => instructions::payroll_rollover::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::SweepExpiredReward(args) => instructions::expired_reward_sweep::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::SealPayroll(args) => instructions::payroll_seal::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::CrankPayroll => instructions::payroll_crank::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::FundCrankBudget(args) => instructions::crank_budget_funding::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::FundCampaign(args) => instructions::campaign_funding::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
+        StakingInstruction::SweepUndistributedEmission => instructions::undistributed_emission_sweep::process_instruction(
+            program_id,
+            accounts,
+        ),
+        StakingInstruction::SweepExpiredMintReward(args) => instructions::expired_mint_reward_sweep::process_instruction(
+            program_id,
+            accounts,
+            args,
+        ),
     }?;
     Ok(())
 }
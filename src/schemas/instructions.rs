@@ -3,4 +3,11 @@ pub mod pool_initialization;
 pub mod reward_redemption;
 pub mod reward_addition;
 pub mod reward_initialization;
-pub mod token_data;
\ No newline at end of file
+pub mod token_data;
+pub mod pool_update;
+pub mod emission_funding;
+pub mod power_rules;
+pub mod pool_collections;
+pub mod payroll_rollover;
+pub mod crank_budget_funding;
+pub mod campaign_funding;
\ No newline at end of file
@@ -0,0 +1,13 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CampaignFundingIns {
+  // split evenly across the payrolls, the first ones get what does not divide
+  pub amount: u64,
+  // first and last payroll index, both included
+  pub start_index: u64,
+  pub end_index: u64,
+}
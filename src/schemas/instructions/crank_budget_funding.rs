@@ -0,0 +1,10 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CrankBudgetFundingIns {
+  // lamports added to the crank budget of the pool
+  pub amount: u64,
+}
@@ -0,0 +1,12 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EmissionFundingIns {
+  // reward tokens per second
+  pub emission_rate: u64,
+
+  pub end_at: u64,
+}
@@ -0,0 +1,12 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PayrollRolloverIns {
+  // finished payroll nobody can claim from
+  pub from_index: u64,
+  // payroll receiving the reward, not claimable yet
+  pub to_index: u64,
+}
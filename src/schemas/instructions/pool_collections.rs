@@ -0,0 +1,12 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+use crate::schemas::states::pool_collections::CollectionPower;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PoolCollectionsIns {
+  // replaces the whole list, at most MAX_POOL_COLLECTIONS
+  pub collections: Vec<CollectionPower>,
+
+}
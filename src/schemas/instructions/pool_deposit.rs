@@ -2,11 +2,23 @@ use borsh::{
   BorshSerialize,
   BorshDeserialize,
 };
-use solana_program::pubkey::Pubkey;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct PoolDepositIns {
-  // url 
-  pub withdrawn_address: Pubkey,
+  // 0 for no lock, otherwise the 1-based index into the pool lock tiers
+  pub lock_tier: u8,
+  // power of the token in the pool power rules merkle tree
+  pub power_proof: Option<PowerProof>,
+  // proof of the mint in the pool allowlist, for tokens outside the collection
+  pub allowlist_proof: Option<Vec<[u8; 32]>>,
 
-}
\ No newline at end of file
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PowerProof {
+
+  pub power: u64,
+  // sibling hashes from the leaf up to the root
+  pub proof: Vec<[u8; 32]>,
+
+}
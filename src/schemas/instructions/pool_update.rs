@@ -0,0 +1,56 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+use solana_program::pubkey::Pubkey;
+use crate::schemas::states::pool::{LockTier, MAX_LOCK_TIERS};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct PoolUpdateIns {
+  // fields left as None are not changed
+  pub name: Option<[u8; 16]>,
+  // schedule can only change before the pool starts
+  pub reward_period: Option<u64>,
+
+  pub start_at: Option<u64>,
+
+  pub pool_type: Option<u8>,
+
+  pub collection: Option<Pubkey>,
+
+  pub power_authority: Option<Pubkey>,
+
+  pub guardian: Option<Pubkey>,
+  // only applies to deposits made after the update
+  pub lock_tiers: Option<[LockTier; MAX_LOCK_TIERS]>,
+  // all zero removes the allowlist
+  pub allowlist_root: Option<[u8; 32]>,
+  // can only be extended once the pool has started, 0 never expires
+  pub claim_expiry: Option<u64>,
+  // payroll rate precision, up to MAX_REWARD_DECIMALS, only before the pool starts
+  pub reward_decimals: Option<u8>,
+  // lamports per payroll crank, paid from the crank budget
+  pub crank_tip: Option<u64>,
+  // 0 removes the end, can only be extended once the pool has started
+  pub end_at: Option<u64>,
+
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PoolCreatorProposalIns {
+
+  pub new_creator: Pubkey,
+
+}
+
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PoolPauseIns {
+
+  pub deposit_paused: bool,
+
+  pub reward_withdrawal_paused: bool,
+
+  pub fund_withdrawal_paused: bool,
+
+}
\ No newline at end of file
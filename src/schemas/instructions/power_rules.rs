@@ -0,0 +1,14 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize,
+};
+use crate::schemas::states::power_rules::{CreatorPower, MAX_CREATOR_POWERS};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PowerRulesIns {
+  // all zero when no merkle powers are used
+  pub merkle_root: [u8; 32],
+
+  pub creator_powers: [CreatorPower; MAX_CREATOR_POWERS],
+
+}
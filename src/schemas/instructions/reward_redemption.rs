@@ -8,4 +8,10 @@ pub struct RewardRedemption {
   // name, 16 char
   pub index: u64,
 
-}
\ No newline at end of file
+}
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RewardRangeRedemption {
+  // first and last payroll index, both included
+  pub start_index: u64,
+  pub end_index: u64,
+}
@@ -2,4 +2,8 @@ pub mod pool;
 pub mod payroll;
 pub mod staking_account;
 pub mod staking_payroll;
-pub mod token_data;
\ No newline at end of file
+pub mod token_data;
+pub mod reward_vault;
+pub mod payroll_reward;
+pub mod power_rules;
+pub mod pool_collections;
\ No newline at end of file
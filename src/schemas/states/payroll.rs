@@ -12,12 +12,20 @@ pub struct Payroll {
     pub total_deposited_power: u64,
     pub reward_withdrawn_amount: u64,
     pub total_reward_amount: u64,
-    pub rate_reward: u64,
+    // reward per power, scaled by 10^reward_decimals of the pool
+    pub rate_reward: u128,
+    // reward the rounded down rate leaves out, once every stake has claimed
+    // it is all the payroll still holds
+    pub reward_remainder: u64,
+    // power of the stakes that have claimed the payroll
+    pub claimed_power: u64,
+    // set once the payroll has ended, its power and rate no longer change
+    pub sealed: bool,
     pub index: u64,
     pub claimable_after: u64,
     pub start_at: u64,
     pub pool_pda_account: Pubkey,
     pub creator: Pubkey,
 }
-pub const PAYROLL_PDA_LEN: usize = 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 32;
+pub const PAYROLL_PDA_LEN: usize = 1 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 32;
 pub const PAYROLL_SEED: &[u8] = b"payroll";
\ No newline at end of file
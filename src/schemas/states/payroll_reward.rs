@@ -0,0 +1,21 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize
+};
+
+use solana_program::{
+  pubkey::Pubkey
+};
+
+// reward of a registered vault mint for a single payroll
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct PayrollReward {
+  pub account_type: u8,
+  pub payroll_pda_account: Pubkey,
+  pub reward_token_mint_address: Pubkey,
+  pub index: u64,
+  pub total_reward_amount: u64,
+  pub reward_withdrawn_amount: u64,
+}
+pub const PAYROLL_REWARD_PDA_LEN: usize = 1 + 32 + 32 + 8 + 8 + 8;
+pub const PAYROLL_REWARD_SEED: &[u8] = b"payrollreward";
@@ -8,6 +8,10 @@ use solana_program::{
 };
 
 
+// the layout is not versioned and there is no migration: every field added
+// since the first release grows the account, so pools created by an earlier
+// deployment no longer deserialize. upgrading means redeploying under a new
+// program id and creating the pools again
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Pool {
     pub account_type: u8,
@@ -23,14 +27,75 @@ pub struct Pool {
     pub reward_token_mint_address: Pubkey,
     
     pub reward_ata: Pubkey,
-    // poolType
+    // poolType, POOL_TYPE_* flags in common
     pub pool_type: u8,
     // creator
     pub creator: Pubkey,
 
     pub collection: Pubkey,
+    // allowed to set the power of tokens staked in this pool
+    pub power_authority: Pubkey,
+    // creator proposed by the current creator, has to accept
+    pub pending_creator: Pubkey,
+    // name and creator the pool pda was derived from, never change
+    pub seed_name: [u8; 16],
 
+    pub seed_creator: Pubkey,
+    // can pause the pool alongside the creator
+    pub guardian: Pubkey,
+    // deposits of nfts and rewards
+    pub deposit_paused: bool,
+
+    pub reward_withdrawal_paused: bool,
+
+    pub fund_withdrawal_paused: bool,
+    // chosen at deposit, unused tiers have a zero duration
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS],
+    // continuous pools, reward tokens emitted per second until emission_end_at
+    pub emission_rate: u64,
+
+    pub emission_end_at: u64,
+    // emission accrued up to, reward per power scaled by DECIMAL_REWARD
+    pub last_accrued_at: u64,
+
+    pub acc_reward_per_power: u128,
+    // root of the merkle tree of mints allowed outside of the collection,
+    // all zero when there is no allowlist
+    pub allowlist_root: [u8; 32],
+    // seconds after claimable_after during which a payroll can be claimed,
+    // 0 when rewards never expire
+    pub claim_expiry: u64,
+    // decimals payroll reward rates are scaled by, 0 keeps DECIMAL_REWARD
+    pub reward_decimals: u8,
+    // power deposited while a payroll runs, it only earns from the payroll
+    // at pending_payroll_index
+    pub pending_power: u64,
+
+    pub pending_payroll_index: u64,
+    // lamports paid to whoever cranks a new payroll, out of crank_budget
+    // which the pool account holds on top of its rent
+    pub crank_tip: u64,
+
+    pub crank_budget: u64,
+    // deposits close at end_at, 0 when the pool never ends
+    pub end_at: u64,
+    // last payroll running before end_at, no reward can be added past it
+    pub max_payroll_index: u64,
+    // continuous pools, emission nobody earned or a new emission left out,
+    // the creator can sweep it
+    pub undistributed_emission: u64,
+
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct LockTier {
+    // seconds the nft stays locked after the deposit
+    pub duration: u64,
+    // applied to the token power, in basis points of MULTIPLIER_BASE
+    pub multiplier: u16,
 }
-pub const POOL_PDA_LEN: usize = 16 + 1 + 8 + 8 + 8 + 32 + 32 + 1 + 32 + 32;
+pub const MAX_LOCK_TIERS: usize = 4;
+pub const LOCK_TIER_LEN: usize = 8 + 2;
+pub const POOL_PDA_LEN: usize = 16 + 1 + 8 + 8 + 8 + 32 + 32 + 1 + 32 + 32 + 32 + 32 + 16 + 32 + 32 + 1 + 1 + 1 + LOCK_TIER_LEN * MAX_LOCK_TIERS + 8 + 8 + 8 + 16 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
 pub const POOL_SEED: &[u8] = b"pool";
 pub const REWADER_SEED: &[u8] = b"rewarder";
\ No newline at end of file
@@ -0,0 +1,30 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize
+};
+
+use solana_program::{
+  pubkey::Pubkey
+};
+
+pub const MAX_POOL_COLLECTIONS: usize = 32;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy)]
+pub struct CollectionPower {
+  pub collection: Pubkey,
+  // power of the tokens of the collection without token data or power rules
+  pub power: u64,
+}
+pub const COLLECTION_POWER_LEN: usize = 32 + 8;
+
+// collections accepted besides the pool collection, resized with the list
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct PoolCollections {
+  pub account_type: u8,
+  pub pool_pda_account: Pubkey,
+  pub collections: Vec<CollectionPower>,
+}
+pub fn get_pool_collections_pda_len(collections_count: usize) -> usize {
+  1 + 32 + 4 + collections_count * COLLECTION_POWER_LEN
+}
+pub const POOL_COLLECTIONS_SEED: &[u8] = b"poolcollections";
@@ -0,0 +1,30 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize
+};
+
+use solana_program::{
+  pubkey::Pubkey
+};
+
+pub const MAX_CREATOR_POWERS: usize = 4;
+
+// power of the tokens a verified creator signed, unused entries have power 0
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct CreatorPower {
+  pub creator: Pubkey,
+  pub power: u64,
+}
+
+// pool wide token powers, token data of a mint takes precedence
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct PowerRules {
+  pub account_type: u8,
+  pub pool_pda_account: Pubkey,
+  // root of the (mint, power) merkle tree, see get_power_leaf
+  pub merkle_root: [u8; 32],
+
+  pub creator_powers: [CreatorPower; MAX_CREATOR_POWERS],
+}
+pub const POWER_RULES_PDA_LEN: usize = 1 + 32 + 32 + MAX_CREATOR_POWERS * (32 + 8);
+pub const POWER_RULES_SEED: &[u8] = b"powerrules";
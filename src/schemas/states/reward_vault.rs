@@ -0,0 +1,20 @@
+use borsh::{
+  BorshSerialize,
+  BorshDeserialize
+};
+
+use solana_program::{
+  pubkey::Pubkey
+};
+
+// an extra reward mint registered on a pool, besides the pool reward token
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
+pub struct RewardVault {
+  pub account_type: u8,
+  pub pool_pda_account: Pubkey,
+  pub reward_token_mint_address: Pubkey,
+  pub total_reward_amount: u64,
+  pub reward_withdrawn_amount: u64,
+}
+pub const REWARD_VAULT_PDA_LEN: usize = 1 + 32 + 32 + 8 + 8;
+pub const REWARD_VAULT_SEED: &[u8] = b"rewardvault";
@@ -7,6 +7,8 @@ use solana_program::{
   pubkey::Pubkey
 };
 
+// not versioned either, see Pool. positions of an earlier deployment have to
+// be withdrawn there before the redeploy
 #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Clone)]
 pub struct StakingAccount {
   pub account_type: u8,
@@ -18,7 +20,20 @@ pub struct StakingAccount {
   pub pool_pda_account: Pubkey,
   pub withdrawn_address: Pubkey,
   pub staking_token_mint_address: Pubkey,
-  pub depositor: Pubkey
+  pub depositor: Pubkey,
+  // the nft cannot be withdrawn before
+  pub locked_until: u64,
+  // lock tier multiplier already applied to deposited_power
+  pub multiplier: u16,
+  // programmable nft, moved through the token metadata program
+  pub programmable: bool,
+  // continuous pools, accrued reward per power already accounted for
+  pub reward_debt: u64,
+  // continuous pools, reward settled at withdrawal and not claimed yet
+  pub accrued_reward: u64,
+  // last payroll a closing position has recorded its forfeit on, the
+  // closure can take several transactions
+  pub settled_payroll_index: u64,
 }
-pub const STAKING_PDA_LEN: usize = 1 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 32 + 32;
+pub const STAKING_PDA_LEN: usize = 1 + 8 + 8 + 8 + 8 + 8 + 32 + 32 + 32 + 32 + 8 + 2 + 1 + 8 + 8 + 8;
 pub const STAKING_SEED: &[u8] = b"staking";
\ No newline at end of file
@@ -9,12 +9,14 @@ use solana_program::{
 };
 
 
+// derived per pool since the power authority, entries of an earlier
+// deployment are not found and have to be created again
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct TokenData {
   pub account_type: u8,
   pub power: u64,
   pub token_mint_address: Pubkey,
+  pub pool_pda_account: Pubkey,
 }
-// 73
-pub const TOKEN_DATA_PDA_LEN: usize = 1 + 8 + 32;
+pub const TOKEN_DATA_PDA_LEN: usize = 1 + 8 + 32 + 32;
 pub const TOKEN_DATA_SEED: &[u8] = b"tokendata";
\ No newline at end of file
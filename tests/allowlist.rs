@@ -0,0 +1,104 @@
+mod common;
+
+use common::*;
+use mpl_token_metadata::types::TokenStandard;
+use solana_program::{keccak::hashv, pubkey::Pubkey};
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::signature::Signer;
+use staking_nft_2::{
+    common::{get_allowlist_leaf, get_staking_pda},
+    error::ContractError,
+    instruction,
+    schemas::{
+        instructions::pool_update::PoolUpdateIns,
+        states::{pool::Pool, staking_account::StakingAccount},
+    },
+};
+
+/// Root of a two leaf tree, each leaf is the proof of the other.
+fn allowlist_root(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    match left <= right {
+        true => hashv(&[&left, &right]).to_bytes(),
+        false => hashv(&[&right, &left]).to_bytes(),
+    }
+}
+
+async fn set_allowlist_root(fixture: &mut Fixture, allowlist_root: [u8; 32]) -> Result<(), BanksClientError> {
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            allowlist_root: Some(allowlist_root),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await
+}
+
+/// An NFT without an on-chain collection, as in older collections.
+async fn create_legacy_nft(fixture: &mut Fixture) -> Pubkey {
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    set_metadata(&mut fixture.context, &nft_mint, None, TokenStandard::NonFungible);
+    nft_mint
+}
+
+#[tokio::test]
+async fn test_deposit_with_allowlist_proof() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = create_legacy_nft(&mut fixture).await;
+    let other_leaf = get_allowlist_leaf(&Pubkey::new_unique());
+    set_allowlist_root(&mut fixture, allowlist_root(get_allowlist_leaf(&nft_mint), other_leaf))
+        .await
+        .unwrap();
+    set_clock(&mut fixture.context, START_AT + 10).await;
+
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 0);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::MissingCollection);
+
+    let ix = instruction::deposit_with_allowlist_proof(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+        vec![other_leaf],
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let (staking_pda, _) =
+        get_staking_pda(&fixture.pool_pda, &fixture.staker.pubkey(), &nft_mint, &fixture.program_id).unwrap();
+    let staking_account: StakingAccount = get_state(&mut fixture.context, &staking_pda).await;
+    assert_eq!(staking_account.deposited_power, 1);
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 1);
+}
+
+#[tokio::test]
+async fn test_deposit_after_allowlist_rotation() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = create_legacy_nft(&mut fixture).await;
+    let other_leaf = get_allowlist_leaf(&Pubkey::new_unique());
+    set_allowlist_root(&mut fixture, allowlist_root(get_allowlist_leaf(&nft_mint), other_leaf))
+        .await
+        .unwrap();
+    // the rotated allowlist no longer has the mint
+    let rotated_root = allowlist_root(get_allowlist_leaf(&Pubkey::new_unique()), other_leaf);
+    set_allowlist_root(&mut fixture, rotated_root).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.allowlist_root, rotated_root);
+    set_clock(&mut fixture.context, START_AT + 10).await;
+
+    let ix = instruction::deposit_with_allowlist_proof(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+        vec![other_leaf],
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidAllowlistProof);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use common::*;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_payroll_pda, get_staking_pda, POOL_TYPE_NON_CUSTODIAL},
+    error::ContractError,
+    instruction,
+    schemas::states::{payroll::Payroll, pool::Pool, staking_account::StakingAccount},
+};
+
+#[tokio::test]
+async fn test_batch_deposit_and_withdraw() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let mut nft_mints = vec![];
+    for _ in 0..5 {
+        nft_mints.push(create_nft(context, &staker.pubkey(), collection, true).await);
+    }
+    let ix = instruction::set_token_data(program_id, &creator.pubkey(), pool_pda, &nft_mints[0], 3);
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 10).await;
+    let ix = instruction::batch_deposit(program_id, &staker.pubkey(), pool_pda, &nft_mints, 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 7);
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_deposited_power, 7);
+    for (i, nft_mint) in nft_mints.iter().enumerate() {
+        let pool_nft_ata = get_associated_token_address(pool_pda, nft_mint);
+        assert_eq!(token_balance(context, &pool_nft_ata).await, 1);
+        let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), nft_mint, program_id).unwrap();
+        let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+        assert_eq!(staking_account.deposited_power, if i == 0 { 3 } else { 1 });
+        assert_eq!(staking_account.first_payroll_index, 2);
+        assert_eq!(staking_account.withdrawn_address, staker.pubkey());
+    }
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    let ix = instruction::batch_withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mints, &staker.pubkey(), 3);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+    let (payroll_pda, _) = get_payroll_pda(3, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_deposited_power, 0);
+    for nft_mint in &nft_mints {
+        let staker_nft_ata = get_associated_token_address(&staker.pubkey(), nft_mint);
+        assert_eq!(token_balance(context, &staker_nft_ata).await, 1);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_withdraw_with_withdrawn_token() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let other_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 1);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::batch_withdraw_fund(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &[other_mint, nft_mint],
+        &staker.pubkey(),
+        1,
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::FundAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_batch_deposit_into_non_custodial_pool() {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_NON_CUSTODIAL).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let nft_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let ix = instruction::batch_deposit(program_id, &staker.pubkey(), pool_pda, &[nft_mint], 1, 0);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::UnsupportedPoolType);
+}
+
+#[tokio::test]
+async fn test_batch_deposit_with_other_token_program() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let nft_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    set_clock(context, START_AT + 10).await;
+    let mut ix = instruction::batch_deposit(program_id, &staker.pubkey(), pool_pda, &[nft_mint], 2, 0);
+    for account in ix.accounts.iter_mut().filter(|account| account.pubkey == spl_token::id()) {
+        *account = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    }
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
+
+#[tokio::test]
+async fn test_batch_deposit_of_withdrawn_position() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 1);
+    process(context, &[ix], &[staker]).await.unwrap();
+    // the position has to be closed first
+    let ix = instruction::batch_deposit(program_id, &staker.pubkey(), pool_pda, &[nft_mint], 2, 0);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::StakingPositionOpen);
+}
+
+#[tokio::test]
+async fn test_batch_withdraw_to_other_token_account() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let mut ix = instruction::batch_withdraw_fund(program_id, &staker.pubkey(), pool_pda, &[nft_mint], &staker.pubkey(), 1);
+    ix.accounts[9] = AccountMeta::new(get_associated_token_address(&creator.pubkey(), &nft_mint), false);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidAtaAccount);
+}
+
+#[tokio::test]
+async fn test_batch_withdraw_with_other_token_program() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let mut ix = instruction::batch_withdraw_fund(program_id, &staker.pubkey(), pool_pda, &[nft_mint], &staker.pubkey(), 1);
+    ix.accounts[4] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
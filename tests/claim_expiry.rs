@@ -0,0 +1,172 @@
+mod common;
+
+use common::*;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_payroll_pda, get_payroll_reward_pda, get_reward_vault_pda},
+    error::ContractError,
+    instruction,
+    schemas::{
+        instructions::pool_update::PoolUpdateIns,
+        states::{payroll::Payroll, payroll_reward::PayrollReward, reward_vault::RewardVault},
+    },
+};
+
+const CLAIM_EXPIRY: u64 = 500;
+
+async fn set_claim_expiry(fixture: &mut Fixture, claim_expiry: u64) -> Result<(), BanksClientError> {
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            claim_expiry: Some(claim_expiry),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await
+}
+
+#[tokio::test]
+async fn test_sweep_expired_reward() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    set_claim_expiry(&mut fixture, CLAIM_EXPIRY).await.unwrap();
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    let creator_reward_ata = get_associated_token_address(&creator.pubkey(), reward_mint);
+    let creator_balance = token_balance(context, &creator_reward_ata).await;
+
+    // still claimable
+    set_clock(context, START_AT + 2 * REWARD_PERIOD + CLAIM_EXPIRY - 1).await;
+    let ix = instruction::sweep_expired_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD + CLAIM_EXPIRY).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::ClaimExpired);
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=3,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::ClaimExpired);
+
+    let ix = instruction::sweep_expired_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    assert_eq!(token_balance(context, &creator_reward_ata).await, creator_balance + 1_000);
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.reward_withdrawn_amount, payroll.total_reward_amount);
+
+    let ix = instruction::sweep_expired_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_sweep_expired_mint_reward() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    set_claim_expiry(&mut fixture, CLAIM_EXPIRY).await.unwrap();
+    let partner_mint = create_mint(&mut fixture.context, 9).await;
+    mint_to(&mut fixture.context, &partner_mint, &fixture.creator.pubkey(), 1_000).await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward_vault(program_id, &creator.pubkey(), pool_pda, &partner_mint);
+    process(context, &[ix], &[creator]).await.unwrap();
+    let ix = instruction::add_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, 900, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    let creator_partner_ata = get_associated_token_address(&creator.pubkey(), &partner_mint);
+    let creator_balance = token_balance(context, &creator_partner_ata).await;
+
+    // still claimable
+    set_clock(context, START_AT + 2 * REWARD_PERIOD + CLAIM_EXPIRY - 1).await;
+    let ix = instruction::sweep_expired_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD + CLAIM_EXPIRY).await;
+    let ix = instruction::withdraw_mint_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        &partner_mint,
+        2,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::ClaimExpired);
+
+    let ix = instruction::sweep_expired_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    assert_eq!(token_balance(context, &creator_partner_ata).await, creator_balance + 900);
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let (payroll_reward_pda, _) = get_payroll_reward_pda(&payroll_pda, &partner_mint, program_id);
+    let payroll_reward: PayrollReward = get_state(context, &payroll_reward_pda).await;
+    assert_eq!(payroll_reward.reward_withdrawn_amount, 900);
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, &partner_mint, program_id);
+    let reward_vault: RewardVault = get_state(context, &reward_vault_pda).await;
+    assert_eq!(reward_vault.reward_withdrawn_amount, 900);
+
+    let ix = instruction::sweep_expired_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_claim_expiry_only_extends_after_start() {
+    let mut fixture = setup_pool().await;
+    stake_nft(&mut fixture).await;
+    let result = set_claim_expiry(&mut fixture, CLAIM_EXPIRY).await;
+    assert_contract_error(result, ContractError::PoolAlreadyStarted);
+    // rewards that never expire stay so
+    set_claim_expiry(&mut fixture, 0).await.unwrap();
+
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    set_claim_expiry(&mut fixture, CLAIM_EXPIRY).await.unwrap();
+    stake_nft(&mut fixture).await;
+    let result = set_claim_expiry(&mut fixture, CLAIM_EXPIRY - 1).await;
+    assert_contract_error(result, ContractError::PoolAlreadyStarted);
+    set_claim_expiry(&mut fixture, CLAIM_EXPIRY + 1).await.unwrap();
+    set_claim_expiry(&mut fixture, 0).await.unwrap();
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_staking_pda, get_staking_payroll_pda},
+    error::ContractError,
+    instruction,
+    schemas::states::{staking_account::StakingAccount, staking_payroll::StakingPayroll},
+};
+
+#[tokio::test]
+async fn test_claim_range_skips_unfunded_payrolls() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    for (index, amount) in [(2, 600_000), (4, 300_000)] {
+        let ix = instruction::add_reward(
+            program_id,
+            &creator.pubkey(),
+            pool_pda,
+            reward_mint,
+            index,
+            amount,
+            &spl_token::id(),
+        );
+        process(context, &[ix], &[creator]).await.unwrap();
+    }
+    set_clock(context, START_AT + 4 * REWARD_PERIOD).await;
+    for index in [2, 4] {
+        seal_payroll(context, program_id, pool_pda, index).await;
+    }
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=4,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 900_000);
+    let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &nft_mint, program_id).unwrap();
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert_eq!(staking_account.withdrawn_reward_amount, 900_000);
+    for (index, amount) in [(2, 600_000), (4, 300_000)] {
+        let (staking_payroll_pda, _) = get_staking_payroll_pda(index, pool_pda, &staking_pda, program_id);
+        let staking_payroll: StakingPayroll = get_state(context, &staking_payroll_pda).await;
+        assert_eq!(staking_payroll.index, index);
+        assert_eq!(staking_payroll.reward_withdrawn_amount, amount);
+    }
+    let (staking_payroll_pda, _) = get_staking_payroll_pda(3, pool_pda, &staking_pda, program_id);
+    assert!(get_account(context, &staking_payroll_pda).await.is_none());
+
+    // claiming the same range again pays nothing
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=4,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_claim_range_after_single_claim() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    for index in [2, 3] {
+        let ix = instruction::add_reward(
+            program_id,
+            &creator.pubkey(),
+            pool_pda,
+            reward_mint,
+            index,
+            1_000,
+            &spl_token::id(),
+        );
+        process(context, &[ix], &[creator]).await.unwrap();
+    }
+    set_clock(context, START_AT + 3 * REWARD_PERIOD).await;
+    for index in [2, 3] {
+        seal_payroll(context, program_id, pool_pda, index).await;
+    }
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=3,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 2_000);
+}
+
+#[tokio::test]
+async fn test_claim_range_before_claimable() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=3,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+}
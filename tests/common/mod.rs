@@ -0,0 +1,414 @@
+#![allow(dead_code)]
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::{
+    accounts::{MasterEdition, Metadata, TokenRecord},
+    types::{Collection, Key, TokenStandard},
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Account as TokenAccount};
+use staking_nft_2::error::ContractError;
+
+pub const START_AT: u64 = 1_700_000_000;
+pub const REWARD_PERIOD: u64 = 100;
+
+pub fn program_test() -> (ProgramTest, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "staking_nft_2",
+        program_id,
+        processor!(staking_nft_2::process_instruction),
+    );
+    program_test.add_program(
+        "mpl_token_metadata",
+        mpl_token_metadata::ID,
+        processor!(mock_token_metadata),
+    );
+    (program_test, program_id)
+}
+
+/// Stands in for the token metadata program, which is not built for
+/// solana-program-test. FreezeDelegatedAccount and ThawDelegatedAccount sign
+/// as the edition PDA that is the mint freeze authority, TransferV1 moves a
+/// programmable NFT between frozen token accounts.
+fn mock_token_metadata(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    match data.first() {
+        Some(26) | Some(27) => mock_delegated_freeze(program_id, accounts, data[0] == 26),
+        Some(49) => mock_programmable_transfer(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn edition_signer_seeds<'s>(program_id: &'s Pubkey, mint: &'s Pubkey, bump: &'s [u8; 1]) -> [&'s [u8]; 5] {
+    [b"metadata", program_id.as_ref(), mint.as_ref(), b"edition", bump]
+}
+
+fn mock_delegated_freeze<'a>(program_id: &Pubkey, accounts: &[AccountInfo<'a>], freeze: bool) -> ProgramResult {
+    let [delegate, token_account, edition, mint, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (edition_pda, bump) = MasterEdition::find_pda(mint.key);
+    if *edition.key != edition_pda {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let account = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+    if !delegate.is_signer || account.delegate != Some(*delegate.key).into() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    edition_freeze(freeze, token_account, mint, edition, token_program, &edition_signer_seeds(program_id, mint.key, &[bump]))
+}
+
+fn mock_programmable_transfer<'a>(program_id: &Pubkey, accounts: &[AccountInfo<'a>]) -> ProgramResult {
+    let [token, _token_owner, destination, destination_owner, mint, _metadata, edition, token_record, destination_token_record, authority, payer, system_program, _sysvar_instructions, token_program, _ata_program, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (edition_pda, bump) = MasterEdition::find_pda(mint.key);
+    if *edition.key != edition_pda
+        || *token_record.key != TokenRecord::find_pda(mint.key, token.key).0
+        || *destination_token_record.key != TokenRecord::find_pda(mint.key, destination.key).0
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if destination.data_len() == 0 {
+        invoke_signed(
+            &create_associated_token_account(payer.key, destination_owner.key, mint.key, token_program.key),
+            &[
+                payer.clone(),
+                destination.clone(),
+                destination_owner.clone(),
+                mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+            ],
+            &[],
+        )?;
+    }
+    let bump = [bump];
+    let seeds = edition_signer_seeds(program_id, mint.key, &bump);
+    edition_freeze(false, token, mint, edition, token_program, &seeds)?;
+    if spl_token::state::Account::unpack(&destination.data.borrow())?.is_frozen() {
+        edition_freeze(false, destination, mint, edition, token_program, &seeds)?;
+    }
+    let ix = spl_token::instruction::transfer(token_program.key, token.key, destination.key, authority.key, &[], 1)?;
+    invoke_signed(&ix, &[token.clone(), destination.clone(), authority.clone()], &[])?;
+    edition_freeze(true, token, mint, edition, token_program, &seeds)?;
+    edition_freeze(true, destination, mint, edition, token_program, &seeds)
+}
+
+fn edition_freeze<'a>(
+    freeze: bool,
+    token_account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    edition: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let ix = match freeze {
+        true => spl_token::instruction::freeze_account(token_program.key, token_account.key, mint.key, edition.key, &[])?,
+        false => spl_token::instruction::thaw_account(token_program.key, token_account.key, mint.key, edition.key, &[])?,
+    };
+    invoke_signed(&ix, &[token_account.clone(), mint.clone(), edition.clone(), token_program.clone()], &[seeds])
+}
+
+pub fn pool_name(name: &str) -> [u8; 16] {
+    let mut pool_name = [0u8; 16];
+    pool_name[..name.len()].copy_from_slice(name.as_bytes());
+    pool_name
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+pub fn assert_contract_error(result: Result<(), BanksClientError>, error: ContractError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, error.clone() as u32, "expected {:?}", error),
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+pub async fn set_clock(context: &mut ProgramTestContext, unix_timestamp: u64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp as i64;
+    context.set_sysvar(&clock);
+}
+
+/// Seals a payroll that has ended, paid by the context payer, so its rewards
+/// can be claimed.
+pub async fn seal_payroll(context: &mut ProgramTestContext, program_id: &Pubkey, pool_pda: &Pubkey, index: u64) {
+    let ix = staking_nft_2::instruction::seal_payroll(program_id, &context.payer.pubkey(), pool_pda, index);
+    process(context, &[ix], &[]).await.unwrap();
+}
+
+pub async fn get_account(context: &mut ProgramTestContext, address: &Pubkey) -> Option<Account> {
+    context.banks_client.get_account(*address).await.unwrap()
+}
+
+/// Reads program state. Some accounts are allocated larger than their
+/// struct, so trailing bytes are ignored.
+pub async fn get_state<T: BorshDeserialize>(context: &mut ProgramTestContext, address: &Pubkey) -> T {
+    let account = get_account(context, address).await.expect("account not found");
+    T::deserialize(&mut &account.data[..]).unwrap()
+}
+
+pub async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    match get_account(context, address).await {
+        // reads both token programs, Token-2022 accounts may carry extensions
+        Some(account) => StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap().base.amount,
+        None => 0,
+    }
+}
+
+pub async fn fund(context: &mut ProgramTestContext, address: &Pubkey, lamports: u64) {
+    let ix = system_instruction::transfer(&context.payer.pubkey(), address, lamports);
+    process(context, &[ix], &[]).await.unwrap();
+}
+
+pub async fn create_mint(context: &mut ProgramTestContext, decimals: u8) -> Pubkey {
+    let mint = Keypair::new();
+    init_mint(context, &mint, decimals, None).await;
+    mint.pubkey()
+}
+
+async fn init_mint(
+    context: &mut ProgramTestContext,
+    mint: &Keypair,
+    decimals: u8,
+    freeze_authority: Option<&Pubkey>,
+) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &payer,
+            freeze_authority,
+            decimals,
+        )
+        .unwrap(),
+    ];
+    process(context, &instructions, &[mint]).await.unwrap();
+}
+
+pub async fn mint_to(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let ata = get_associated_token_address(owner, mint);
+    let mut instructions = vec![];
+    if get_account(context, &ata).await.is_none() {
+        instructions.push(create_associated_token_account(&payer, owner, mint, &spl_token::id()));
+    }
+    instructions.push(
+        spl_token::instruction::mint_to(&spl_token::id(), mint, &ata, &payer, &[], amount).unwrap(),
+    );
+    process(context, &instructions, &[]).await.unwrap();
+    ata
+}
+
+/// Writes a Metaplex metadata account for `mint` without going through the
+/// token metadata program.
+pub fn set_metadata(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    collection: Option<Collection>,
+    token_standard: TokenStandard,
+) {
+    let (metadata_pda, _) = Metadata::find_pda(mint);
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: context.payer.pubkey(),
+        mint: *mint,
+        name: "Staking NFT".to_string(),
+        symbol: "SNFT".to_string(),
+        uri: "https://codehub.io".to_string(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(token_standard),
+        collection,
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    };
+    let data = metadata.try_to_vec().unwrap();
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &mpl_token_metadata::ID);
+    account.set_data_from_slice(&data);
+    context.set_account(&metadata_pda, &account);
+}
+
+/// Mints a 0 decimal token to `owner` and gives it metadata in `collection`.
+/// The edition PDA is the freeze authority, as for a Metaplex NFT.
+pub async fn create_nft(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    collection: &Pubkey,
+    verified: bool,
+) -> Pubkey {
+    create_nft_with_standard(context, owner, collection, verified, TokenStandard::NonFungible).await
+}
+
+/// Same as `create_nft` for a programmable NFT, whose token account stays
+/// frozen by the edition outside of token metadata transfers.
+pub async fn create_programmable_nft(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    collection: &Pubkey,
+) -> Pubkey {
+    let mint = create_nft_with_standard(
+        context,
+        owner,
+        collection,
+        true,
+        TokenStandard::ProgrammableNonFungible,
+    )
+    .await;
+    let ata = get_associated_token_address(owner, &mint);
+    let mut account = context.banks_client.get_account(ata).await.unwrap().unwrap();
+    let mut token_account = spl_token::state::Account::unpack(&account.data).unwrap();
+    token_account.state = spl_token::state::AccountState::Frozen;
+    spl_token::state::Account::pack(token_account, &mut account.data).unwrap();
+    context.set_account(&ata, &account.into());
+    mint
+}
+
+async fn create_nft_with_standard(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    collection: &Pubkey,
+    verified: bool,
+    token_standard: TokenStandard,
+) -> Pubkey {
+    let mint_keypair = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    let (edition_pda, _) = MasterEdition::find_pda(&mint);
+    init_mint(context, &mint_keypair, 0, Some(&edition_pda)).await;
+    mint_to(context, &mint, owner, 1).await;
+    set_metadata(
+        context,
+        &mint,
+        Some(Collection {
+            verified,
+            key: *collection,
+        }),
+        token_standard,
+    );
+    mint
+}
+
+pub struct Fixture {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    pub creator: Keypair,
+    pub staker: Keypair,
+    pub reward_mint: Pubkey,
+    pub collection: Pubkey,
+    pub pool_pda: Pubkey,
+}
+
+/// Starts the program with a funded creator and staker and a pool that
+/// starts at `START_AT`, with the clock set to that time.
+pub async fn setup_pool() -> Fixture {
+    setup_pool_with_type(0).await
+}
+
+pub async fn setup_pool_with_type(pool_type: u8) -> Fixture {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, START_AT).await;
+    let creator = Keypair::new();
+    let staker = Keypair::new();
+    fund(&mut context, &creator.pubkey(), 10_000_000_000).await;
+    fund(&mut context, &staker.pubkey(), 10_000_000_000).await;
+    let reward_mint = create_mint(&mut context, 6).await;
+    mint_to(&mut context, &reward_mint, &creator.pubkey(), 1_000_000_000).await;
+    let collection = Pubkey::new_unique();
+    let name = pool_name("lifecycle");
+    let (pool_pda, _) = staking_nft_2::common::get_pool_pda(&name, &creator.pubkey(), &program_id);
+    let ix = staking_nft_2::instruction::initialize_pool(
+        &program_id,
+        &creator.pubkey(),
+        &reward_mint,
+        staking_nft_2::schemas::instructions::pool_initialization::PoolInitializationIns {
+            name,
+            reward_period: REWARD_PERIOD,
+            start_at: START_AT,
+            creator: creator.pubkey(),
+            collection,
+            pool_type,
+        },
+        &spl_token::id(),
+    );
+    process(&mut context, &[ix], &[&creator]).await.unwrap();
+    Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    }
+}
+
+/// Deposits a fresh NFT of the pool collection for the staker at
+/// `START_AT + 10`, so it earns from payroll 2 onwards.
+pub async fn stake_nft(fixture: &mut Fixture) -> Pubkey {
+    let context = &mut fixture.context;
+    set_clock(context, START_AT + 10).await;
+    let nft_mint = create_nft(context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let ix = staking_nft_2::instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    process(context, &[ix], &[&fixture.staker]).await.unwrap();
+    nft_mint
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use std::ops::RangeInclusive;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_staking_pda, POOL_TYPE_CONTINUOUS},
+    error::ContractError,
+    instruction,
+    schemas::states::{pool::Pool, staking_account::StakingAccount},
+};
+
+const EMISSION_RATE: u64 = 10;
+const EMISSION_END_AT: u64 = START_AT + 1_000;
+
+async fn setup_continuous_pool() -> Fixture {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_CONTINUOUS).await;
+    let ix = instruction::fund_emission(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        EMISSION_RATE,
+        EMISSION_END_AT,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    fixture
+}
+
+async fn claim(fixture: &mut Fixture, nft_mint: &Pubkey) -> u64 {
+    let ix = instruction::claim_emission(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&fixture.staker.pubkey(), &fixture.reward_mint);
+    token_balance(&mut fixture.context, &staker_reward_ata).await
+}
+
+#[tokio::test]
+async fn test_fund_and_claim_emission() {
+    let mut fixture = setup_continuous_pool().await;
+    let pool_reward_ata = get_associated_token_address(&fixture.pool_pda, &fixture.reward_mint);
+    assert_eq!(token_balance(&mut fixture.context, &pool_reward_ata).await, EMISSION_RATE * 1_000);
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.emission_rate, EMISSION_RATE);
+    assert_eq!(pool.emission_end_at, EMISSION_END_AT);
+
+    // staked at START_AT + 10, the only staker earns the whole rate
+    let nft_mint = stake_nft(&mut fixture).await;
+    set_clock(&mut fixture.context, START_AT + 110).await;
+    assert_eq!(claim(&mut fixture, &nft_mint).await, 100 * EMISSION_RATE);
+
+    // nothing accrues past the end of the emission
+    set_clock(&mut fixture.context, EMISSION_END_AT + 500).await;
+    assert_eq!(claim(&mut fixture, &nft_mint).await, 990 * EMISSION_RATE);
+    let (staking_pda, _) = get_staking_pda(
+        &fixture.pool_pda,
+        &fixture.staker.pubkey(),
+        &nft_mint,
+        &fixture.program_id,
+    )
+    .unwrap();
+    let staking_account: StakingAccount = get_state(&mut fixture.context, &staking_pda).await;
+    assert_eq!(staking_account.withdrawn_reward_amount, 990 * EMISSION_RATE);
+    assert_eq!(staking_account.accrued_reward, 0);
+
+    let ix = instruction::claim_emission(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_emission_split_between_stakes() {
+    let mut fixture = setup_continuous_pool().await;
+    let first_mint = stake_nft(&mut fixture).await;
+    set_clock(&mut fixture.context, START_AT + 60).await;
+    let second_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &second_mint, 1, 0);
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+
+    // 50 seconds alone, then 50 seconds at half the rate
+    set_clock(&mut fixture.context, START_AT + 110).await;
+    let first_claimed = claim(&mut fixture, &first_mint).await;
+    assert_eq!(first_claimed, 50 * EMISSION_RATE + 50 * EMISSION_RATE / 2);
+    assert_eq!(claim(&mut fixture, &second_mint).await - first_claimed, 50 * EMISSION_RATE / 2);
+}
+
+#[tokio::test]
+async fn test_withdraw_settles_emission() {
+    let mut fixture = setup_continuous_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    set_clock(&mut fixture.context, START_AT + 60).await;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+
+    // the withdrawn stake keeps what it earned and earns nothing more
+    set_clock(&mut fixture.context, START_AT + 200).await;
+    assert_eq!(claim(&mut fixture, &nft_mint).await, 50 * EMISSION_RATE);
+}
+
+#[tokio::test]
+async fn test_payroll_reward_on_continuous_pool() {
+    let mut fixture = setup_continuous_pool().await;
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::UnsupportedPoolType);
+
+    let mut fixture = setup_pool().await;
+    let ix = instruction::fund_emission(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        EMISSION_RATE,
+        EMISSION_END_AT,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::UnsupportedPoolType);
+}
+
+#[tokio::test]
+async fn test_fund_emission_overflow() {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_CONTINUOUS).await;
+    let ix = instruction::fund_emission(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        u64::MAX,
+        EMISSION_END_AT,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::MathOverflow);
+}
+
+#[tokio::test]
+async fn test_sweep_undistributed_emission() {
+    let mut fixture = setup_continuous_pool().await;
+    // nobody is staked for the first 10 seconds
+    let nft_mint = stake_nft(&mut fixture).await;
+    set_clock(&mut fixture.context, START_AT + 110).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::sweep_undistributed_emission(program_id, &staker.pubkey(), pool_pda, reward_mint, &spl_token::id());
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPoolCreator);
+    let creator_reward_ata = get_associated_token_address(&creator.pubkey(), reward_mint);
+    let creator_balance = token_balance(context, &creator_reward_ata).await;
+    let sweep_ix = || {
+        instruction::sweep_undistributed_emission(program_id, &creator.pubkey(), pool_pda, reward_mint, &spl_token::id())
+    };
+    process(context, &[sweep_ix()], &[creator]).await.unwrap();
+    assert_eq!(token_balance(context, &creator_reward_ata).await, creator_balance + 10 * EMISSION_RATE);
+    let result = process(context, &[sweep_ix()], &[creator]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+
+    // halving the rate leaves half of the rest of the emission unpaid
+    let ix = instruction::fund_emission(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        reward_mint,
+        EMISSION_RATE / 2,
+        EMISSION_END_AT,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.undistributed_emission, 890 * EMISSION_RATE / 2);
+    process(context, &[sweep_ix()], &[creator]).await.unwrap();
+
+    // the staker gets the rest, nothing stays behind in the pool
+    set_clock(context, EMISSION_END_AT).await;
+    let ix = instruction::claim_emission(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), reward_mint, &spl_token::id());
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 100 * EMISSION_RATE + 890 * EMISSION_RATE / 2);
+    let pool_reward_ata = get_associated_token_address(pool_pda, reward_mint);
+    assert_eq!(token_balance(context, &pool_reward_ata).await, 0);
+}
+
+#[tokio::test]
+async fn test_close_position_with_unclaimed_emission() {
+    let mut fixture = setup_continuous_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    set_clock(&mut fixture.context, START_AT + 60).await;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let close_ix = instruction::close_staking_position(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        RangeInclusive::new(1, 0),
+        &[],
+    );
+    let result = process(&mut fixture.context, std::slice::from_ref(&close_ix), &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::RewardNotClaimed);
+    assert_eq!(claim(&mut fixture, &nft_mint).await, 50 * EMISSION_RATE);
+    process(&mut fixture.context, &[close_ix], &[&fixture.staker]).await.unwrap();
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::*;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::signature::Signer;
+use staking_nft_2::{
+    common::get_staking_pda,
+    error::ContractError,
+    instruction,
+    schemas::{
+        instructions::pool_update::PoolUpdateIns,
+        states::{
+            pool::{LockTier, Pool, MAX_LOCK_TIERS},
+            staking_account::StakingAccount,
+        },
+    },
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn lock_tiers() -> [LockTier; MAX_LOCK_TIERS] {
+    [
+        LockTier { duration: 30 * DAY, multiplier: 10_000 },
+        LockTier { duration: 90 * DAY, multiplier: 15_000 },
+        LockTier { duration: 180 * DAY, multiplier: 20_000 },
+        LockTier::default(),
+    ]
+}
+
+async fn set_lock_tiers(
+    fixture: &mut Fixture,
+    lock_tiers: [LockTier; MAX_LOCK_TIERS],
+) -> Result<(), BanksClientError> {
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            lock_tiers: Some(lock_tiers),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await
+}
+
+#[tokio::test]
+async fn test_locked_deposit() {
+    let mut fixture = setup_pool().await;
+    set_lock_tiers(&mut fixture, lock_tiers()).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.lock_tiers, lock_tiers());
+
+    // 90 days at 1.5x on a token of power 3
+    let deposited_at = START_AT + 10;
+    set_clock(&mut fixture.context, deposited_at).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let ix = instruction::set_token_data(&fixture.program_id, &fixture.creator.pubkey(), &fixture.pool_pda, &nft_mint, 3);
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 2);
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let (staking_pda, _) =
+        get_staking_pda(&fixture.pool_pda, &fixture.staker.pubkey(), &nft_mint, &fixture.program_id).unwrap();
+    let staking_account: StakingAccount = get_state(&mut fixture.context, &staking_pda).await;
+    assert_eq!(staking_account.deposited_power, 4);
+    assert_eq!(staking_account.locked_until, deposited_at + 90 * DAY);
+    assert_eq!(staking_account.multiplier, 15_000);
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 4);
+
+    // withdrawing before the lock ends fails
+    let unlocked_at = deposited_at + 90 * DAY;
+    set_clock(&mut fixture.context, unlocked_at - 1).await;
+    let payroll_index = (unlocked_at - 1 - START_AT) / REWARD_PERIOD + 1;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        payroll_index,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::StillLocked);
+
+    set_clock(&mut fixture.context, unlocked_at).await;
+    let payroll_index = (unlocked_at - START_AT) / REWARD_PERIOD + 1;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        payroll_index,
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+}
+
+#[tokio::test]
+async fn test_deposit_with_unused_lock_tier() {
+    let mut fixture = setup_pool().await;
+    set_lock_tiers(&mut fixture, lock_tiers()).await.unwrap();
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    for lock_tier in [4, 5] {
+        let ix = instruction::deposit(
+            &fixture.program_id,
+            &fixture.staker.pubkey(),
+            &fixture.pool_pda,
+            &nft_mint,
+            2,
+            lock_tier,
+        );
+        let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+        assert_contract_error(result, ContractError::InvalidLockTier);
+    }
+}
+
+#[tokio::test]
+async fn test_lock_tier_below_one() {
+    let mut fixture = setup_pool().await;
+    let mut tiers = lock_tiers();
+    tiers[3] = LockTier { duration: DAY, multiplier: 9_999 };
+    let result = set_lock_tiers(&mut fixture, tiers).await;
+    assert_contract_error(result, ContractError::InvalidLockTier);
+}
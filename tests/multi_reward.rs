@@ -0,0 +1,196 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{
+        get_mint_staking_payroll_pda, get_payroll_pda, get_payroll_reward_pda, get_reward_vault_pda,
+        get_rewarder_pda, get_staking_pda, PAYROLL_REWARD_ACCOUNT_TYPE, REWARD_VAULT_ACCOUNT_TYPE,
+    },
+    error::ContractError,
+    instruction,
+    schemas::states::{
+        payroll::Payroll, payroll_reward::PayrollReward, reward_vault::RewardVault,
+        staking_payroll::StakingPayroll,
+    },
+};
+
+#[tokio::test]
+async fn test_claim_each_reward_mint() {
+    let mut fixture = setup_pool().await;
+    let partner_mint = create_mint(&mut fixture.context, 9).await;
+    mint_to(&mut fixture.context, &partner_mint, &fixture.creator.pubkey(), 1_000_000).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    } = &mut fixture;
+
+    // register the partner mint
+    let ix = instruction::add_reward_vault(program_id, &creator.pubkey(), pool_pda, &partner_mint);
+    process(context, &[ix], &[creator]).await.unwrap();
+    let (reward_vault_pda, _) = get_reward_vault_pda(pool_pda, &partner_mint, program_id);
+    let reward_vault: RewardVault = get_state(context, &reward_vault_pda).await;
+    assert_eq!(
+        reward_vault,
+        RewardVault {
+            account_type: REWARD_VAULT_ACCOUNT_TYPE,
+            pool_pda_account: *pool_pda,
+            reward_token_mint_address: partner_mint,
+            total_reward_amount: 0,
+            reward_withdrawn_amount: 0,
+        }
+    );
+
+    // fund payroll 2 in both mints, the partner mint opens the payroll
+    let ix = instruction::add_mint_reward(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        &partner_mint,
+        2,
+        900,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    let ix = instruction::add_reward(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        reward_mint,
+        2,
+        600_000,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    let (payroll_reward_pda, _) = get_payroll_reward_pda(&payroll_pda, &partner_mint, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_reward_amount, 600_000);
+    let payroll_reward: PayrollReward = get_state(context, &payroll_reward_pda).await;
+    assert_eq!(
+        payroll_reward,
+        PayrollReward {
+            account_type: PAYROLL_REWARD_ACCOUNT_TYPE,
+            payroll_pda_account: payroll_pda,
+            reward_token_mint_address: partner_mint,
+            index: 2,
+            total_reward_amount: 900,
+            reward_withdrawn_amount: 0,
+        }
+    );
+    let rewarder_partner_ata = get_associated_token_address(&rewarder_pda, &partner_mint);
+    assert_eq!(token_balance(context, &rewarder_partner_ata).await, 900);
+
+    // deposit and claim both mints for the same payroll
+    set_clock(context, START_AT + 10).await;
+    let nft_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::withdraw_mint_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        &partner_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_partner_ata = get_associated_token_address(&staker.pubkey(), &partner_mint);
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_partner_ata).await, 900);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 600_000);
+    assert_eq!(token_balance(context, &rewarder_partner_ata).await, 0);
+    let payroll_reward: PayrollReward = get_state(context, &payroll_reward_pda).await;
+    assert_eq!(payroll_reward.reward_withdrawn_amount, 900);
+    let reward_vault: RewardVault = get_state(context, &reward_vault_pda).await;
+    assert_eq!(reward_vault.total_reward_amount, 900);
+    assert_eq!(reward_vault.reward_withdrawn_amount, 900);
+    let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &nft_mint, program_id).unwrap();
+    let (staking_payroll_pda, _) =
+        get_mint_staking_payroll_pda(2, pool_pda, &staking_pda, &partner_mint, program_id);
+    let staking_payroll: StakingPayroll = get_state(context, &staking_payroll_pda).await;
+    assert_eq!(staking_payroll.reward_withdrawn_amount, 900);
+    assert_eq!(staking_payroll.total_reward_amount, 900);
+
+    // a second claim of the partner mint pays nothing
+    let ix = instruction::withdraw_mint_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        &partner_mint,
+        2,
+        &spl_token::id(),
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_add_reward_vault_by_other_signer() {
+    let mut fixture = setup_pool().await;
+    let partner_mint = create_mint(&mut fixture.context, 6).await;
+    let ix = instruction::add_reward_vault(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &partner_mint,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPoolCreator);
+}
+
+#[tokio::test]
+async fn test_add_reward_vault_for_pool_reward_token() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::add_reward_vault(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidRewardToken);
+}
+
+#[tokio::test]
+async fn test_add_mint_reward_without_vault() {
+    let mut fixture = setup_pool().await;
+    let partner_mint = create_mint(&mut fixture.context, 6).await;
+    mint_to(&mut fixture.context, &partner_mint, &fixture.creator.pubkey(), 1_000).await;
+    let ix = instruction::add_mint_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &partner_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
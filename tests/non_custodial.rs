@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState};
+use staking_nft_2::{
+    common::POOL_TYPE_NON_CUSTODIAL,
+    error::ContractError,
+    instruction,
+    schemas::states::pool::Pool,
+};
+
+async fn token_account(context: &mut ProgramTestContext, address: &Pubkey) -> TokenAccount {
+    let account = get_account(context, address).await.unwrap();
+    TokenAccount::unpack(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_non_custodial_deposit_and_withdraw() {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_NON_CUSTODIAL).await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+
+    // the nft stays with the staker, frozen and delegated to the pool
+    let staker_nft_ata = get_associated_token_address(&staker.pubkey(), &nft_mint);
+    let staked = token_account(context, &staker_nft_ata).await;
+    assert_eq!(staked.amount, 1);
+    assert_eq!(staked.state, AccountState::Frozen);
+    assert_eq!(staked.delegate, COption::Some(*pool_pda));
+    assert_eq!(staked.delegated_amount, 1);
+    let pool_nft_ata = get_associated_token_address(pool_pda, &nft_mint);
+    assert!(get_account(context, &pool_nft_ata).await.is_none());
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 1);
+
+    // it cannot be moved while staked
+    let other = Keypair::new();
+    let other_ata = mint_to(context, &nft_mint, &other.pubkey(), 0).await;
+    let ix = spl_token::instruction::transfer(&spl_token::id(), &staker_nft_ata, &other_ata, &staker.pubkey(), &[], 1)
+        .unwrap();
+    assert!(process(context, &[ix], &[staker]).await.is_err());
+
+    // withdrawing thaws and revokes
+    let ix = instruction::withdraw_delegated_fund(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        1,
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let withdrawn = token_account(context, &staker_nft_ata).await;
+    assert_eq!(withdrawn.amount, 1);
+    assert_eq!(withdrawn.state, AccountState::Initialized);
+    assert_eq!(withdrawn.delegate, COption::None);
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+}
+
+#[tokio::test]
+async fn test_non_custodial_deposit_of_frozen_token() {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_NON_CUSTODIAL).await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    // the staking position of the frozen token is still open
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::StakingPositionOpen);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::{Keypair, Signer}};
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::get_payroll_pda,
+    error::ContractError,
+    instruction,
+    schemas::{instructions::pool_update::PoolUpdateIns, states::{payroll::Payroll, pool::Pool}},
+};
+
+const CRANK_TIP: u64 = 5_000;
+
+async fn lamports(fixture: &mut Fixture, address: &Pubkey) -> u64 {
+    get_account(&mut fixture.context, address).await.unwrap().lamports
+}
+
+#[tokio::test]
+async fn test_crank_payroll_pays_tip_once() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            crank_tip: Some(CRANK_TIP),
+            ..PoolUpdateIns::default()
+        },
+    );
+    let fund_ix = instruction::fund_crank_budget(&fixture.program_id, &fixture.creator.pubkey(), &fixture.pool_pda, CRANK_TIP);
+    process(&mut fixture.context, &[ix, fund_ix], &[&fixture.creator]).await.unwrap();
+    // rewards of payroll 4 are added while nothing is staked
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        4,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let nft_mint = stake_nft(&mut fixture).await;
+    let cranker = Keypair::new();
+    fund(&mut fixture.context, &cranker.pubkey(), 1_000_000_000).await;
+
+    // nobody touches the pool until payroll 4 runs
+    set_clock(&mut fixture.context, START_AT + 3 * REWARD_PERIOD + 10).await;
+    let pool_pda = fixture.pool_pda;
+    let pool_lamports = lamports(&mut fixture, &pool_pda).await;
+    let ix = instruction::crank_payroll(&fixture.program_id, &cranker.pubkey(), &pool_pda, 4);
+    process(&mut fixture.context, &[ix], &[&cranker]).await.unwrap();
+    assert_eq!(lamports(&mut fixture, &pool_pda).await, pool_lamports - CRANK_TIP);
+    let pool: Pool = get_state(&mut fixture.context, &pool_pda).await;
+    assert_eq!(pool.crank_budget, 0);
+    for index in [4, 5] {
+        let (payroll_pda, _) = get_payroll_pda(index, &pool_pda, &fixture.program_id);
+        let payroll: Payroll = get_state(&mut fixture.context, &payroll_pda).await;
+        assert_eq!(payroll.total_deposited_power, 1);
+    }
+
+    // both payrolls exist now, cranking again is not paid
+    let pool_lamports = lamports(&mut fixture, &pool_pda).await;
+    let ix = instruction::crank_payroll(&fixture.program_id, &cranker.pubkey(), &pool_pda, 4);
+    process(&mut fixture.context, &[ix], &[&cranker]).await.unwrap();
+    assert_eq!(lamports(&mut fixture, &pool_pda).await, pool_lamports);
+
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        reward_mint,
+        ..
+    } = &mut fixture;
+    set_clock(context, START_AT + 4 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, &pool_pda, 4).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        &pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        4,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_crank_payroll_errors() {
+    let mut fixture = setup_pool().await;
+    let cranker = Keypair::new();
+    fund(&mut fixture.context, &cranker.pubkey(), 1_000_000_000).await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    let ix = instruction::crank_payroll(&fixture.program_id, &cranker.pubkey(), &fixture.pool_pda, 1);
+    let result = process(&mut fixture.context, &[ix], &[&cranker]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+
+    // the payroll accounts have to match the running payroll
+    set_clock(&mut fixture.context, START_AT + REWARD_PERIOD + 10).await;
+    let ix = instruction::crank_payroll(&fixture.program_id, &cranker.pubkey(), &fixture.pool_pda, 1);
+    let result = process(&mut fixture.context, &[ix], &[&cranker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+
+    let ix = instruction::fund_crank_budget(&fixture.program_id, &cranker.pubkey(), &fixture.pool_pda, 0);
+    let result = process(&mut fixture.context, &[ix], &[&cranker]).await;
+    assert_contract_error(result, ContractError::InvalidDepositAmount);
+}
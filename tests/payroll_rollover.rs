@@ -0,0 +1,112 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_payroll_pda, get_rewarder_pda},
+    error::ContractError,
+    instruction,
+    schemas::states::payroll::Payroll,
+};
+
+#[tokio::test]
+async fn test_rollover_payroll_without_power() {
+    let mut fixture = setup_pool().await;
+    // payroll 1 is funded before anyone stakes, the stake earns from payroll 2
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        1,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    set_clock(context, START_AT + REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 1).await;
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 1, 3, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+
+    let (from_payroll_pda, _) = get_payroll_pda(1, pool_pda, program_id);
+    let (from_rewarder_pda, _) = get_rewarder_pda(&from_payroll_pda, pool_pda, program_id);
+    let from_payroll: Payroll = get_state(context, &from_payroll_pda).await;
+    assert_eq!(from_payroll.total_reward_amount, 0);
+    assert_eq!(token_balance(context, &get_associated_token_address(&from_rewarder_pda, reward_mint)).await, 0);
+    let (to_payroll_pda, _) = get_payroll_pda(3, pool_pda, program_id);
+    let to_payroll: Payroll = get_state(context, &to_payroll_pda).await;
+    assert_eq!(to_payroll.total_reward_amount, 1_000);
+    assert_eq!(to_payroll.total_deposited_power, 1);
+
+    // nothing left to move
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 1, 3, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::NoRolloverReward);
+
+    set_clock(context, START_AT + 3 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 3).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        3,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 1_000);
+}
+
+#[tokio::test]
+async fn test_rollover_payroll_errors() {
+    let mut fixture = setup_pool().await;
+    stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+
+    // payroll 2 is still running
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+
+    set_clock(context, START_AT + 3 * REWARD_PERIOD).await;
+    // the target payroll is already claimable
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 3, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+    // the stake can claim payroll 2
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::PayrollNotSealed);
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::NoRolloverReward);
+    let ix = instruction::rollover_payroll(program_id, &staker.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id());
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPoolCreator);
+}
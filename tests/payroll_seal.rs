@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::get_payroll_pda,
+    error::ContractError,
+    instruction,
+    schemas::states::{payroll::Payroll, pool::Pool},
+};
+
+#[tokio::test]
+async fn test_withdrawal_during_payroll_does_not_dilute_it() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let withdrawn_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+
+    // while payroll 2 runs, a deposit that earns from payroll 3 and a
+    // withdrawal that gives up payroll 2
+    set_clock(context, START_AT + REWARD_PERIOD + 10).await;
+    let pending_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &pending_mint, 3, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &withdrawn_mint, &staker.pubkey(), 2);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 2);
+    assert_eq!(pool.pending_power, 1);
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_deposited_power, 1);
+
+    let payer = context.payer.pubkey();
+    let ix = instruction::seal_payroll(program_id, &payer, pool_pda, 2);
+    let result = process(context, &[ix], &[]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    let withdraw_reward_ix = || {
+        instruction::withdraw_reward(
+            program_id,
+            &staker.pubkey(),
+            pool_pda,
+            &nft_mint,
+            &staker.pubkey(),
+            reward_mint,
+            2,
+            &spl_token::id(),
+        )
+    };
+    let result = process(context, &[withdraw_reward_ix()], &[staker]).await;
+    assert_contract_error(result, ContractError::PayrollNotSealed);
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert!(payroll.sealed);
+    assert_eq!(payroll.total_deposited_power, 1);
+
+    let ix = instruction::seal_payroll(program_id, &payer, pool_pda, 2);
+    let result = process(context, &[ix], &[]).await;
+    assert_contract_error(result, ContractError::PayrollSealed);
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::PayrollSealed);
+
+    process(context, &[withdraw_reward_ix()], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 1_000);
+}
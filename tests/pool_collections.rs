@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+use staking_nft_2::{
+    common::{get_pool_collections_pda, get_staking_pda},
+    error::ContractError,
+    instruction,
+    schemas::states::{
+        pool::Pool,
+        pool_collections::{get_pool_collections_pda_len, CollectionPower, PoolCollections},
+        staking_account::StakingAccount,
+    },
+};
+
+async fn deposit(fixture: &mut Fixture, collection: &Pubkey, verified: bool) -> Result<u64, ContractError> {
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), collection, verified).await;
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 0);
+    if let Err(error) = process(&mut fixture.context, &[ix], &[&fixture.staker]).await {
+        assert_contract_error(Err(error), ContractError::InvalidCollection);
+        return Err(ContractError::InvalidCollection);
+    }
+    let (staking_pda, _) =
+        get_staking_pda(&fixture.pool_pda, &fixture.staker.pubkey(), &nft_mint, &fixture.program_id).unwrap();
+    let staking_account: StakingAccount = get_state(&mut fixture.context, &staking_pda).await;
+    Ok(staking_account.deposited_power)
+}
+
+#[tokio::test]
+async fn test_deposit_from_pool_collections() {
+    let mut fixture = setup_pool().await;
+    let partners = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let ix = instruction::set_pool_collections(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        vec![
+            CollectionPower { collection: partners[0], power: 3 },
+            CollectionPower { collection: partners[1], power: 2 },
+        ],
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    set_clock(&mut fixture.context, START_AT + 10).await;
+
+    let collection = fixture.collection;
+    assert_eq!(deposit(&mut fixture, &collection, true).await, Ok(1));
+    assert_eq!(deposit(&mut fixture, &partners[0], true).await, Ok(3));
+    assert_eq!(deposit(&mut fixture, &partners[1], true).await, Ok(2));
+    assert_eq!(deposit(&mut fixture, &partners[1], false).await, Err(ContractError::InvalidCollection));
+    assert_eq!(deposit(&mut fixture, &Pubkey::new_unique(), true).await, Err(ContractError::InvalidCollection));
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 6);
+}
+
+#[tokio::test]
+async fn test_resize_pool_collections() {
+    let mut fixture = setup_pool().await;
+    let partners = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let (pool_collections_pda, _) = get_pool_collections_pda(&fixture.pool_pda, &fixture.program_id);
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    for count in [3, 1, 2] {
+        let collections = partners[..count]
+            .iter()
+            .map(|collection| CollectionPower { collection: *collection, power: 2 })
+            .collect::<Vec<_>>();
+        let ix = instruction::set_pool_collections(
+            &fixture.program_id,
+            &fixture.creator.pubkey(),
+            &fixture.pool_pda,
+            collections.clone(),
+        );
+        process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+        let account = get_account(&mut fixture.context, &pool_collections_pda).await.unwrap();
+        assert_eq!(account.data.len(), get_pool_collections_pda_len(count));
+        assert_eq!(account.lamports, rent.minimum_balance(get_pool_collections_pda_len(count)));
+        let pool_collections: PoolCollections = get_state(&mut fixture.context, &pool_collections_pda).await;
+        assert_eq!(pool_collections.collections, collections);
+    }
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    assert_eq!(deposit(&mut fixture, &partners[1], true).await, Ok(2));
+    assert_eq!(deposit(&mut fixture, &partners[2], true).await, Err(ContractError::InvalidCollection));
+}
+
+#[tokio::test]
+async fn test_set_invalid_pool_collections() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::set_pool_collections(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        vec![CollectionPower { collection: Pubkey::new_unique(), power: 0 }],
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidCollection);
+
+    let attacker = Keypair::new();
+    fund(&mut fixture.context, &attacker.pubkey(), 1_000_000_000).await;
+    let ix = instruction::set_pool_collections(
+        &fixture.program_id,
+        &attacker.pubkey(),
+        &fixture.pool_pda,
+        vec![CollectionPower { collection: Pubkey::new_unique(), power: 5 }],
+    );
+    let result = process(&mut fixture.context, &[ix], &[&attacker]).await;
+    assert_contract_error(result, ContractError::InvalidPoolCreator);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::*;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use std::ops::RangeInclusive;
+use staking_nft_2::{
+    common::get_payroll_pda,
+    error::ContractError,
+    instruction,
+    schemas::{instructions::pool_update::PoolUpdateIns, states::{payroll::Payroll, pool::Pool}},
+};
+
+async fn set_end_at(fixture: &mut Fixture, end_at: u64) -> Result<(), BanksClientError> {
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            end_at: Some(end_at),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await
+}
+
+#[tokio::test]
+async fn test_pool_end_closes_deposits_and_rewards() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    set_end_at(&mut fixture, START_AT + 3 * REWARD_PERIOD).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.max_payroll_index, 3);
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    } = &mut fixture;
+
+    let ix = instruction::fund_campaign(program_id, &creator.pubkey(), pool_pda, reward_mint, 2..=3, 1_001, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    for (index, amount) in [(2, 501), (3, 500)] {
+        let (payroll_pda, _) = get_payroll_pda(index, pool_pda, program_id);
+        let payroll: Payroll = get_state(context, &payroll_pda).await;
+        assert_eq!(payroll.total_reward_amount, amount);
+        assert_eq!(payroll.total_deposited_power, 1);
+    }
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 4, 1_000, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::PayrollPastPoolEnd);
+    let ix = instruction::fund_campaign(program_id, &creator.pubkey(), pool_pda, reward_mint, 3..=4, 1_000, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::PayrollPastPoolEnd);
+
+    // once the pool has ended stakers only leave
+    set_clock(context, START_AT + 3 * REWARD_PERIOD).await;
+    let late_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &late_mint, 5, 0);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::PoolEnded);
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    seal_payroll(context, program_id, pool_pda, 3).await;
+    let ix = instruction::claim_range(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2..=3,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 1_001);
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 4);
+    process(context, &[ix], &[staker]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pool_end_errors() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    let result = set_end_at(&mut fixture, START_AT - 10).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+    let result = set_end_at(&mut fixture, START_AT).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+    set_end_at(&mut fixture, START_AT + 2 * REWARD_PERIOD + 1).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.max_payroll_index, 3);
+
+    // the end of a started pool can only move later
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let result = set_end_at(&mut fixture, START_AT + REWARD_PERIOD).await;
+    assert_contract_error(result, ContractError::PoolAlreadyStarted);
+    set_end_at(&mut fixture, 0).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.max_payroll_index, 0);
+
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::fund_campaign(program_id, &creator.pubkey(), pool_pda, reward_mint, RangeInclusive::new(3, 2), 1_000, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+    let ix = instruction::fund_campaign(program_id, &creator.pubkey(), pool_pda, reward_mint, 2..=4, 2, &spl_token::id());
+    let result = process(context, &[ix], &[creator]).await;
+    assert_contract_error(result, ContractError::InvalidDepositAmount);
+}
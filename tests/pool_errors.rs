@@ -0,0 +1,612 @@
+mod common;
+
+use common::*;
+use mpl_token_metadata::{accounts::Metadata, types::TokenStandard};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::{
+    account::AccountSharedData,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_payroll_pda, get_staking_pda, get_staking_payroll_pda},
+    error::ContractError,
+    instruction,
+    schemas::instructions::{
+        pool_initialization::PoolInitializationIns,
+        pool_update::{PoolPauseIns, PoolUpdateIns},
+    },
+};
+
+#[tokio::test]
+async fn test_initialize_pool_from_program_account() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let creator = Keypair::new();
+    context.set_account(
+        &creator.pubkey(),
+        &AccountSharedData::new(10_000_000_000, 0, &spl_token::id()),
+    );
+    let reward_mint = create_mint(&mut context, 6).await;
+    let ix = instruction::initialize_pool(
+        &program_id,
+        &creator.pubkey(),
+        &reward_mint,
+        PoolInitializationIns {
+            name: pool_name("errors"),
+            reward_period: REWARD_PERIOD,
+            start_at: START_AT,
+            creator: creator.pubkey(),
+            collection: Pubkey::new_unique(),
+            pool_type: 0,
+        },
+        &spl_token::id(),
+    );
+    let result = process(&mut context, &[ix], &[&creator]).await;
+    assert_contract_error(result, ContractError::NotASystemAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_by_other_signer() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let attacker = Keypair::new();
+    fund(&mut fixture.context, &attacker.pubkey(), 1_000_000_000).await;
+    let mut ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    ix.accounts[0] = AccountMeta::new(attacker.pubkey(), true);
+    let result = process(&mut fixture.context, &[ix], &[&attacker]).await;
+    assert_contract_error(result, ContractError::InvalidDepositor);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_of_other_mint() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let other_mint = stake_nft(&mut fixture).await;
+    let mut ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    ix.accounts[4] = AccountMeta::new_readonly(other_mint, false);
+    ix.accounts[5] = AccountMeta::new(get_associated_token_address(&fixture.pool_pda, &other_mint), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_from_pool_account_of_other_nft() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let other_mint = stake_nft(&mut fixture).await;
+    let mut ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    ix.accounts[5] = AccountMeta::new(get_associated_token_address(&fixture.pool_pda, &other_mint), false);
+    ix.accounts[6] = AccountMeta::new(get_associated_token_address(&fixture.staker.pubkey(), &other_mint), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidAtaAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_to_other_token_account() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let mut ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    ix.accounts[6] = AccountMeta::new(get_associated_token_address(&fixture.creator.pubkey(), &nft_mint), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidAtaAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_with_other_token_program() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let mut ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    ix.accounts[8] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_twice() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    process(&mut fixture.context, std::slice::from_ref(&ix), &[&fixture.staker]).await.unwrap();
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::FundAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_withdraw_reward_with_other_staking_payroll() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    set_clock(&mut fixture.context, START_AT + 2 * REWARD_PERIOD).await;
+    let mut ix = instruction::withdraw_reward(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    let (staking_pda, _) = get_staking_pda(
+        &fixture.pool_pda,
+        &fixture.staker.pubkey(),
+        &nft_mint,
+        &fixture.program_id,
+    )
+    .unwrap();
+    let (other_staking_payroll, _) = get_staking_payroll_pda(3, &fixture.pool_pda, &staking_pda, &fixture.program_id);
+    ix.accounts[4] = AccountMeta::new(other_staking_payroll, false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::RewardAlreadyWithdrawn);
+}
+
+#[tokio::test]
+async fn test_withdraw_reward_with_staking_account_of_other_pool() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let name = pool_name("other");
+    let (other_pool_pda, _) = staking_nft_2::common::get_pool_pda(&name, &fixture.creator.pubkey(), &fixture.program_id);
+    let ix = instruction::initialize_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.reward_mint,
+        PoolInitializationIns {
+            name,
+            reward_period: REWARD_PERIOD,
+            start_at: START_AT,
+            creator: fixture.creator.pubkey(),
+            collection: fixture.collection,
+            pool_type: 0,
+        },
+        &spl_token::id(),
+    );
+    let add_reward_ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &other_pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix, add_reward_ix], &[&fixture.creator]).await.unwrap();
+    set_clock(&mut fixture.context, START_AT + 2 * REWARD_PERIOD).await;
+    let mut ix = instruction::withdraw_reward(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &other_pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    let (staking_pda, _) = get_staking_pda(
+        &fixture.pool_pda,
+        &fixture.staker.pubkey(),
+        &nft_mint,
+        &fixture.program_id,
+    )
+    .unwrap();
+    ix.accounts[1] = AccountMeta::new(staking_pda, false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_reward_before_claimable() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let ix = instruction::withdraw_reward(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidTimeRange);
+}
+
+#[tokio::test]
+async fn test_deposit_with_other_staking_account() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let mut ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    ix.accounts[1] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_deposit_into_own_token_account() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let mut ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    // the nft would stay with the staker
+    ix.accounts[5] = AccountMeta::new(get_associated_token_address(&fixture.staker.pubkey(), &nft_mint), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidAtaAccount);
+}
+
+#[tokio::test]
+async fn test_deposit_with_other_token_program() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let mut ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    for account in ix.accounts.iter_mut().filter(|account| account.pubkey == spl_token::id()) {
+        *account = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    }
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
+
+#[tokio::test]
+async fn test_deposit_of_withdrawn_position() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        1,
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.staker]).await.unwrap();
+    // the position has to be closed first
+    let ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::StakingPositionOpen);
+}
+
+#[tokio::test]
+async fn test_add_reward_from_other_token_account() {
+    let mut fixture = setup_pool().await;
+    let mut ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    let other_ata = get_associated_token_address(&fixture.staker.pubkey(), &fixture.reward_mint);
+    ix.accounts[4] = AccountMeta::new(other_ata, false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidAtaAccount);
+}
+
+#[tokio::test]
+async fn test_add_reward_to_payroll_of_other_index() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        3,
+        1_000,
+        &spl_token::id(),
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let mut ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &fixture.reward_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    let (other_payroll_pda, _) = get_payroll_pda(3, &fixture.pool_pda, &fixture.program_id);
+    ix.accounts[6] = AccountMeta::new(other_payroll_pda, false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_update_pool_by_other_signer() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            name: Some(pool_name("renamed")),
+            ..PoolUpdateIns::default()
+        },
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPoolCreator);
+}
+
+#[tokio::test]
+async fn test_add_reward_in_other_token() {
+    let mut fixture = setup_pool().await;
+    let other_mint = create_mint(&mut fixture.context, 6).await;
+    mint_to(&mut fixture.context, &other_mint, &fixture.creator.pubkey(), 1_000).await;
+    let ix = instruction::add_reward(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        &other_mint,
+        2,
+        1_000,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidRewardToken);
+}
+
+#[tokio::test]
+async fn test_withdraw_reward_without_payroll() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::withdraw_reward(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.staker.pubkey(),
+        &fixture.reward_mint,
+        5,
+        &spl_token::id(),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::NoRewardPayroll);
+}
+
+#[tokio::test]
+async fn test_withdraw_fund_to_other_address() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::withdraw_fund(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        &fixture.creator.pubkey(),
+        1,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidWithdrawnAddress);
+}
+
+#[tokio::test]
+async fn test_set_token_data_for_unknown_pool() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::set_token_data(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        3,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
+
+#[tokio::test]
+async fn test_deposit_token_not_held() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.creator.pubkey(), &fixture.collection, true).await;
+    let ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::TransferError);
+}
+
+#[tokio::test]
+async fn test_deposit_unverified_collection() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, false).await;
+    let ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidCollection);
+}
+
+#[tokio::test]
+async fn test_deposit_without_collection() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    set_metadata(&mut fixture.context, &nft_mint, None, TokenStandard::NonFungible);
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 0);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::MissingCollection);
+}
+
+#[tokio::test]
+async fn test_deposit_with_metadata_of_other_mint() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &Pubkey::new_unique(), true).await;
+    let other_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let mut ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 0);
+    ix.accounts[10] = AccountMeta::new_readonly(Metadata::find_pda(&other_mint).0, false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_deposit_token_with_supply() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    mint_to(&mut fixture.context, &nft_mint, &Pubkey::new_unique(), 1).await;
+    let ix = instruction::deposit(&fixture.program_id, &fixture.staker.pubkey(), &fixture.pool_pda, &nft_mint, 2, 0);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidStakingMint);
+}
+
+#[tokio::test]
+async fn test_set_token_data_by_other_signer() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::set_token_data(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &Pubkey::new_unique(),
+        1_000,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidPowerAuthority);
+}
+
+#[tokio::test]
+async fn test_update_schedule_after_start() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            reward_period: Some(REWARD_PERIOD * 2),
+            ..PoolUpdateIns::default()
+        },
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.creator]).await;
+    assert_contract_error(result, ContractError::PoolAlreadyStarted);
+}
+
+#[tokio::test]
+async fn test_deposit_while_paused() {
+    let mut fixture = setup_pool().await;
+    let ix = instruction::set_pool_pause(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolPauseIns {
+            deposit_paused: true,
+            reward_withdrawal_paused: false,
+            fund_withdrawal_paused: false,
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    set_clock(&mut fixture.context, START_AT + 10).await;
+    let nft_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let ix = instruction::deposit(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2,
+        0,
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::PoolPaused);
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use common::*;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{
+        get_payroll_pda, get_rewarder_pda, get_staking_pda, get_staking_payroll_pda, get_token_data_pda,
+        POOL_ACCOUNT_TYPE, POOL_PAYROLL_ACCOUNT_TYPE, STAKING_ACCOUNT_TYPE, STAKING_PAYROLL_ACCOUNT_TYPE,
+        TOKEN_DATA_ACCOUNT_TYPE, MULTIPLIER_BASE,
+    },
+    instruction,
+    schemas::states::{
+        payroll::Payroll, pool::Pool, staking_account::StakingAccount, staking_payroll::StakingPayroll,
+        token_data::TokenData,
+    },
+};
+
+#[tokio::test]
+async fn test_pool_lifecycle() {
+    let Fixture {
+        mut context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    } = setup_pool().await;
+    let context = &mut context;
+
+    // init pool
+    let pool: Pool = get_state(context, &pool_pda).await;
+    assert_eq!(pool.account_type, POOL_ACCOUNT_TYPE);
+    assert_eq!(pool.name, pool_name("lifecycle"));
+    assert_eq!(pool.total_deposited_power, 0);
+    assert_eq!(pool.reward_period, REWARD_PERIOD);
+    assert_eq!(pool.start_at, START_AT);
+    assert_eq!(pool.reward_token_mint_address, reward_mint);
+    assert_eq!(pool.reward_ata, get_associated_token_address(&pool_pda, &reward_mint));
+    assert_eq!(pool.creator, creator.pubkey());
+    assert_eq!(pool.power_authority, creator.pubkey());
+    assert_eq!(pool.collection, collection);
+    assert_eq!(token_balance(context, &pool.reward_ata).await, 0);
+
+    // set token power
+    let nft_mint = create_nft(context, &staker.pubkey(), &collection, true).await;
+    let ix = instruction::set_token_data(&program_id, &creator.pubkey(), &pool_pda, &nft_mint, 3);
+    process(context, &[ix], &[&creator]).await.unwrap();
+    let (token_data_pda, _) = get_token_data_pda(&nft_mint, &pool_pda, &program_id);
+    let token_data: TokenData = get_state(context, &token_data_pda).await;
+    assert_eq!(token_data.account_type, TOKEN_DATA_ACCOUNT_TYPE);
+    assert_eq!(token_data.power, 3);
+    assert_eq!(token_data.token_mint_address, nft_mint);
+    assert_eq!(token_data.pool_pda_account, pool_pda);
+
+    // add reward to the payroll the deposit will start from
+    let deposited_at = START_AT + 10;
+    set_clock(context, deposited_at).await;
+    let payroll_index = 2;
+    let ix = instruction::add_reward(
+        &program_id,
+        &creator.pubkey(),
+        &pool_pda,
+        &reward_mint,
+        payroll_index,
+        600_000,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[&creator]).await.unwrap();
+    let (payroll_pda, _) = get_payroll_pda(payroll_index, &pool_pda, &program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, &pool_pda, &program_id);
+    let rewarder_ata = get_associated_token_address(&rewarder_pda, &reward_mint);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.account_type, POOL_PAYROLL_ACCOUNT_TYPE);
+    assert_eq!(payroll.total_deposited_power, 0);
+    assert_eq!(payroll.total_reward_amount, 600_000);
+    assert_eq!(payroll.reward_withdrawn_amount, 0);
+    assert_eq!(payroll.rate_reward, 0);
+    assert_eq!(payroll.index, payroll_index);
+    assert_eq!(payroll.claimable_after, START_AT + 2 * REWARD_PERIOD);
+    assert_eq!(payroll.pool_pda_account, pool_pda);
+    assert_eq!(token_balance(context, &rewarder_ata).await, 600_000);
+    let creator_reward_ata = get_associated_token_address(&creator.pubkey(), &reward_mint);
+    assert_eq!(token_balance(context, &creator_reward_ata).await, 1_000_000_000 - 600_000);
+
+    // deposit nft
+    let ix = instruction::deposit(&program_id, &staker.pubkey(), &pool_pda, &nft_mint, payroll_index, 0);
+    process(context, &[ix], &[&staker]).await.unwrap();
+    let (staking_pda, _) = get_staking_pda(&pool_pda, &staker.pubkey(), &nft_mint, &program_id).unwrap();
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert_eq!(
+        staking_account,
+        StakingAccount {
+            account_type: STAKING_ACCOUNT_TYPE,
+            deposited_power: 3,
+            deposited_at,
+            withdrawn_at: 0,
+            first_payroll_index: payroll_index,
+            withdrawn_reward_amount: 0,
+            pool_pda_account: pool_pda,
+            withdrawn_address: staker.pubkey(),
+            staking_token_mint_address: nft_mint,
+            depositor: staker.pubkey(),
+            locked_until: deposited_at,
+            multiplier: MULTIPLIER_BASE as u16,
+            programmable: false,
+            reward_debt: 0,
+            accrued_reward: 0,
+            settled_payroll_index: 0,
+        }
+    );
+    let pool: Pool = get_state(context, &pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 3);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_deposited_power, 3);
+    assert_eq!(payroll.total_reward_amount, 600_000);
+    assert_eq!(payroll.rate_reward, 200_000_000_000);
+    let staker_nft_ata = get_associated_token_address(&staker.pubkey(), &nft_mint);
+    let pool_nft_ata = get_associated_token_address(&pool_pda, &nft_mint);
+    assert_eq!(token_balance(context, &staker_nft_ata).await, 0);
+    assert_eq!(token_balance(context, &pool_nft_ata).await, 1);
+
+    // warp to the end of the payroll and claim
+    let claimed_at = START_AT + 2 * REWARD_PERIOD;
+    set_clock(context, claimed_at).await;
+    seal_payroll(context, &program_id, &pool_pda, 2).await;
+    let ix = instruction::withdraw_reward(
+        &program_id,
+        &staker.pubkey(),
+        &pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        &reward_mint,
+        payroll_index,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[&staker]).await.unwrap();
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), &reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 600_000);
+    assert_eq!(token_balance(context, &rewarder_ata).await, 0);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.reward_withdrawn_amount, 600_000);
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert_eq!(staking_account.withdrawn_reward_amount, 600_000);
+    let (staking_payroll_pda, _) = get_staking_payroll_pda(payroll_index, &pool_pda, &staking_pda, &program_id);
+    let staking_payroll: StakingPayroll = get_state(context, &staking_payroll_pda).await;
+    assert_eq!(
+        staking_payroll,
+        StakingPayroll {
+            account_type: STAKING_PAYROLL_ACCOUNT_TYPE,
+            staking_pda_account: staking_pda,
+            deposited_power: 3,
+            total_pool_deposited_power: 3,
+            total_reward_amount: 600_000,
+            reward_withdrawn_amount: 600_000,
+            index: payroll_index,
+            withdrawn_at: claimed_at,
+        }
+    );
+
+    // withdraw nft during payroll 3
+    let withdrawn_at = claimed_at + 1;
+    set_clock(context, withdrawn_at).await;
+    let ix = instruction::withdraw_fund(&program_id, &staker.pubkey(), &pool_pda, &nft_mint, &staker.pubkey(), 3);
+    process(context, &[ix], &[&staker]).await.unwrap();
+    assert_eq!(token_balance(context, &staker_nft_ata).await, 1);
+    assert_eq!(token_balance(context, &pool_nft_ata).await, 0);
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert_eq!(staking_account.withdrawn_at, withdrawn_at);
+    let pool: Pool = get_state(context, &pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+    let (current_payroll_pda, _) = get_payroll_pda(3, &pool_pda, &program_id);
+    assert!(get_account(context, &current_payroll_pda).await.is_some());
+}
@@ -0,0 +1,319 @@
+mod common;
+
+use common::*;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::{get_payroll_pda, get_staking_pda, get_staking_payroll_pda},
+    error::ContractError,
+    instruction,
+    schemas::{
+        instructions::pool_update::PoolUpdateIns,
+        states::{payroll::Payroll, staking_account::StakingAccount},
+    },
+};
+
+#[tokio::test]
+async fn test_close_position_after_exit() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        reward_mint,
+        2,
+        600_000,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 3);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &nft_mint, program_id).unwrap();
+    let (staking_payroll_pda, _) = get_staking_payroll_pda(2, pool_pda, &staking_pda, program_id);
+    let pool_nft_ata = get_associated_token_address(pool_pda, &nft_mint);
+    let mut reclaimed = 0;
+    for address in [staking_pda, staking_payroll_pda, pool_nft_ata] {
+        reclaimed += get_account(context, &address).await.unwrap().lamports;
+    }
+    let staker_lamports = get_account(context, &staker.pubkey()).await.unwrap().lamports;
+    let ix = instruction::close_staking_position(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        2..=2,
+        &[],
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    for address in [staking_pda, staking_payroll_pda, pool_nft_ata] {
+        assert!(get_account(context, &address).await.is_none());
+    }
+    let staker_account = get_account(context, &staker.pubkey()).await.unwrap();
+    assert_eq!(staker_account.lamports, staker_lamports + reclaimed);
+
+    // the nft can be staked again from a fresh position
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &nft_mint, 4, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+    assert_eq!(token_balance(context, &pool_nft_ata).await, 1);
+}
+
+#[tokio::test]
+async fn test_close_position_before_withdrawal() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let ix = instruction::close_staking_position(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2..=2,
+        &[],
+    );
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::FundNotWithdrawn);
+}
+
+#[tokio::test]
+async fn test_close_position_with_other_token_program() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let mut ix = instruction::close_staking_position(
+        &fixture.program_id,
+        &fixture.staker.pubkey(),
+        &fixture.pool_pda,
+        &nft_mint,
+        2..=2,
+        &[],
+    );
+    ix.accounts[5] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    let result = process(&mut fixture.context, &[ix], &[&fixture.staker]).await;
+    assert_contract_error(result, ContractError::InvalidProgramAccount);
+}
+
+#[tokio::test]
+async fn test_close_position_with_other_staking_payroll() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let other_mint = create_nft(&mut fixture.context, &fixture.staker.pubkey(), &fixture.collection, true).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &other_mint, 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::add_reward(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        reward_mint,
+        2,
+        600_000,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &other_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 3);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    // the staking payroll of the other position cannot be closed with this one
+    let (other_staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &other_mint, program_id).unwrap();
+    let (other_staking_payroll_pda, _) = get_staking_payroll_pda(2, pool_pda, &other_staking_pda, program_id);
+    let ix = instruction::close_staking_position(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        2..=2,
+        &[other_staking_payroll_pda],
+    );
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+}
+
+#[tokio::test]
+async fn test_close_position_forfeits_unclaimed_payrolls() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let forfeited_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &forfeited_mint, &staker.pubkey(), 3);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let close_ix = || {
+        instruction::close_staking_position(program_id, &staker.pubkey(), pool_pda, &forfeited_mint, 2..=2, &[])
+    };
+    let result = process(context, &[close_ix()], &[staker]).await;
+    assert_contract_error(result, ContractError::PayrollNotSealed);
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    process(context, &[close_ix()], &[staker]).await.unwrap();
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.claimed_power, 1);
+
+    // once the other stake has claimed, the forfeited share can be rolled over
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.reward_remainder, 500);
+    let ix = instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    let (to_payroll_pda, _) = get_payroll_pda(4, pool_pda, program_id);
+    let to_payroll: Payroll = get_state(context, &to_payroll_pda).await;
+    assert_eq!(to_payroll.total_reward_amount, 500);
+}
+
+#[tokio::test]
+async fn test_close_position_over_several_transactions() {
+    let mut fixture = setup_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    for index in 2..=3 {
+        let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, index, 1_000, &spl_token::id());
+        process(context, &[ix], &[creator]).await.unwrap();
+    }
+    set_clock(context, START_AT + 3 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    seal_payroll(context, program_id, pool_pda, 3).await;
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 4);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    // the first page only settles payroll 2, the position stays open
+    let ix = instruction::close_staking_position(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2..=2, &[]);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &nft_mint, program_id).unwrap();
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert_eq!(staking_account.settled_payroll_index, 2);
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.claimed_power, 1);
+
+    // a settled payroll can't be passed again
+    let ix = instruction::close_staking_position(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2..=3, &[]);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPdaAccount);
+
+    let ix = instruction::close_staking_position(program_id, &staker.pubkey(), pool_pda, &nft_mint, 3..=3, &[]);
+    process(context, &[ix], &[staker]).await.unwrap();
+    assert!(get_account(context, &staking_pda).await.is_none());
+    let (payroll_pda, _) = get_payroll_pda(3, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.claimed_power, 1);
+    let payroll: Payroll = get_state(context, &get_payroll_pda(2, pool_pda, program_id).0).await;
+    assert_eq!(payroll.claimed_power, 1);
+}
+
+#[tokio::test]
+async fn test_close_position_leaves_mint_reward_to_sweep() {
+    const CLAIM_EXPIRY: u64 = 500;
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            claim_expiry: Some(CLAIM_EXPIRY),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await.unwrap();
+    let partner_mint = create_mint(&mut fixture.context, 9).await;
+    mint_to(&mut fixture.context, &partner_mint, &fixture.creator.pubkey(), 1_000).await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward_vault(program_id, &creator.pubkey(), pool_pda, &partner_mint);
+    process(context, &[ix], &[creator]).await.unwrap();
+    let ix = instruction::add_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, 900, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::withdraw_fund(program_id, &staker.pubkey(), pool_pda, &nft_mint, &staker.pubkey(), 3);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::close_staking_position(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2..=2, &[]);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    // the forfeited share of the mint is swept with the rest once expired
+    let creator_partner_ata = get_associated_token_address(&creator.pubkey(), &partner_mint);
+    let creator_balance = token_balance(context, &creator_partner_ata).await;
+    set_clock(context, START_AT + 2 * REWARD_PERIOD + CLAIM_EXPIRY).await;
+    let ix = instruction::sweep_expired_mint_reward(program_id, &creator.pubkey(), pool_pda, &partner_mint, 2, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    assert_eq!(token_balance(context, &creator_partner_ata).await, creator_balance + 900);
+}
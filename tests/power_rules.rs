@@ -0,0 +1,206 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use mpl_token_metadata::{accounts::Metadata, types::Creator};
+use solana_program::{keccak::hashv, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use staking_nft_2::{
+    common::{get_power_leaf, get_staking_pda},
+    error::ContractError,
+    instruction,
+    schemas::{
+        instructions::{pool_deposit::PowerProof, power_rules::PowerRulesIns},
+        states::{
+            power_rules::{CreatorPower, MAX_CREATOR_POWERS},
+            staking_account::StakingAccount,
+        },
+    },
+};
+
+fn hash_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    match left <= right {
+        true => hashv(&[&left, &right]).to_bytes(),
+        false => hashv(&[&right, &left]).to_bytes(),
+    }
+}
+
+/// Root and proofs of a four leaf tree.
+fn power_tree(leaves: [[u8; 32]; 4]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let left = hash_pair(leaves[0], leaves[1]);
+    let right = hash_pair(leaves[2], leaves[3]);
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[3], left],
+        vec![leaves[2], left],
+    ];
+    (hash_pair(left, right), proofs)
+}
+
+fn power_rules(merkle_root: [u8; 32], creator_powers: &[CreatorPower]) -> PowerRulesIns {
+    let mut rules = PowerRulesIns {
+        merkle_root,
+        creator_powers: [CreatorPower::default(); MAX_CREATOR_POWERS],
+    };
+    rules.creator_powers[..creator_powers.len()].copy_from_slice(creator_powers);
+    rules
+}
+
+async fn set_creator(context: &mut ProgramTestContext, mint: &Pubkey, creator: &Pubkey) {
+    let (metadata_pda, _) = Metadata::find_pda(mint);
+    let mut account = get_account(context, &metadata_pda).await.unwrap();
+    let mut metadata = Metadata::safe_deserialize(&account.data).unwrap();
+    metadata.creators = Some(vec![Creator {
+        address: *creator,
+        verified: true,
+        share: 100,
+    }]);
+    account.data = metadata.try_to_vec().unwrap();
+    context.set_account(&metadata_pda, &account.into());
+}
+
+async fn deposited_power(fixture: &mut Fixture, mint: &Pubkey) -> u64 {
+    let (staking_pda, _) =
+        get_staking_pda(&fixture.pool_pda, &fixture.staker.pubkey(), mint, &fixture.program_id).unwrap();
+    let staking_account: StakingAccount = get_state(&mut fixture.context, &staking_pda).await;
+    staking_account.deposited_power
+}
+
+#[tokio::test]
+async fn test_deposit_with_power_proof() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let rare_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let common_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let (merkle_root, proofs) = power_tree([
+        get_power_leaf(&Pubkey::new_unique(), 3),
+        get_power_leaf(&rare_mint, 5),
+        get_power_leaf(&common_mint, 2),
+        get_power_leaf(&Pubkey::new_unique(), 7),
+    ]);
+    let ix = instruction::set_power_rules(program_id, &creator.pubkey(), pool_pda, power_rules(merkle_root, &[]));
+    process(context, &[ix], &[creator]).await.unwrap();
+
+    set_clock(context, START_AT + 10).await;
+    let ix = instruction::deposit_with_power_proof(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &rare_mint,
+        2,
+        0,
+        PowerProof {
+            power: 5,
+            proof: proofs[1].clone(),
+        },
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    // without a proof the token falls back to the default power
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &common_mint, 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    assert_eq!(deposited_power(&mut fixture, &rare_mint).await, 5);
+    assert_eq!(deposited_power(&mut fixture, &common_mint).await, 1);
+}
+
+#[tokio::test]
+async fn test_deposit_with_invalid_power_proof() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let nft_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+    let (merkle_root, proofs) = power_tree([
+        get_power_leaf(&nft_mint, 2),
+        get_power_leaf(&Pubkey::new_unique(), 9),
+        get_power_leaf(&Pubkey::new_unique(), 3),
+        get_power_leaf(&Pubkey::new_unique(), 7),
+    ]);
+    set_clock(context, START_AT + 10).await;
+    let proof = PowerProof {
+        power: 9,
+        proof: proofs[0].clone(),
+    };
+    // no power rules yet
+    let ix = instruction::deposit_with_power_proof(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2, 0, proof.clone());
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPowerProof);
+
+    let ix = instruction::set_power_rules(program_id, &creator.pubkey(), pool_pda, power_rules(merkle_root, &[]));
+    process(context, &[ix], &[creator]).await.unwrap();
+    let ix = instruction::deposit_with_power_proof(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2, 0, proof);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::InvalidPowerProof);
+}
+
+#[tokio::test]
+async fn test_deposit_with_creator_power() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let artist = Pubkey::new_unique();
+    let ix = instruction::set_power_rules(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        power_rules([0; 32], &[CreatorPower { creator: artist, power: 4 }]),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    let mut nft_mints = vec![];
+    for _ in 0..3 {
+        let nft_mint = create_nft(context, &staker.pubkey(), collection, true).await;
+        set_creator(context, &nft_mint, &artist).await;
+        nft_mints.push(nft_mint);
+    }
+    // token data still overrides the power rules
+    let ix = instruction::set_token_data(program_id, &creator.pubkey(), pool_pda, &nft_mints[0], 2);
+    process(context, &[ix], &[creator]).await.unwrap();
+
+    set_clock(context, START_AT + 10).await;
+    let ix = instruction::deposit(program_id, &staker.pubkey(), pool_pda, &nft_mints[0], 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+    let ix = instruction::batch_deposit(program_id, &staker.pubkey(), pool_pda, &nft_mints[1..], 2, 0);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    assert_eq!(deposited_power(&mut fixture, &nft_mints[0]).await, 2);
+    assert_eq!(deposited_power(&mut fixture, &nft_mints[1]).await, 4);
+    assert_eq!(deposited_power(&mut fixture, &nft_mints[2]).await, 4);
+}
+
+#[tokio::test]
+async fn test_set_power_rules_by_other_signer() {
+    let mut fixture = setup_pool().await;
+    let attacker = Keypair::new();
+    fund(&mut fixture.context, &attacker.pubkey(), 1_000_000_000).await;
+    let ix = instruction::set_power_rules(
+        &fixture.program_id,
+        &attacker.pubkey(),
+        &fixture.pool_pda,
+        power_rules([1; 32], &[]),
+    );
+    let result = process(&mut fixture.context, &[ix], &[&attacker]).await;
+    assert_contract_error(result, ContractError::InvalidPowerAuthority);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState};
+use staking_nft_2::{
+    common::{get_staking_pda, POOL_TYPE_NON_CUSTODIAL},
+    error::ContractError,
+    instruction,
+    schemas::states::{pool::Pool, staking_account::StakingAccount},
+};
+
+async fn token_account(context: &mut ProgramTestContext, address: &Pubkey) -> TokenAccount {
+    let account = get_account(context, address).await.unwrap();
+    TokenAccount::unpack(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_programmable_deposit_and_withdraw() {
+    let mut fixture = setup_pool().await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    set_clock(context, START_AT + 10).await;
+    let nft_mint = create_programmable_nft(context, &staker.pubkey(), collection).await;
+    let ix = instruction::deposit_programmable(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2, 0, None);
+    process(context, &[ix], &[staker]).await.unwrap();
+
+    // the pool holds the nft, still frozen by the token metadata program
+    let staker_nft_ata = get_associated_token_address(&staker.pubkey(), &nft_mint);
+    let pool_nft_ata = get_associated_token_address(pool_pda, &nft_mint);
+    assert_eq!(token_account(context, &staker_nft_ata).await.amount, 0);
+    let staked = token_account(context, &pool_nft_ata).await;
+    assert_eq!(staked.amount, 1);
+    assert_eq!(staked.state, AccountState::Frozen);
+    let (staking_pda, _) = get_staking_pda(pool_pda, &staker.pubkey(), &nft_mint, program_id).unwrap();
+    let staking_account: StakingAccount = get_state(context, &staking_pda).await;
+    assert!(staking_account.programmable);
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 1);
+
+    let ix = instruction::withdraw_programmable_fund(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        1,
+        None,
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let withdrawn = token_account(context, &staker_nft_ata).await;
+    assert_eq!(withdrawn.amount, 1);
+    assert_eq!(withdrawn.state, AccountState::Frozen);
+    assert_eq!(token_account(context, &pool_nft_ata).await.amount, 0);
+    let pool: Pool = get_state(context, pool_pda).await;
+    assert_eq!(pool.total_deposited_power, 0);
+}
+
+#[tokio::test]
+async fn test_programmable_deposit_into_non_custodial_pool() {
+    let mut fixture = setup_pool_with_type(POOL_TYPE_NON_CUSTODIAL).await;
+    let Fixture {
+        context,
+        program_id,
+        staker,
+        collection,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    set_clock(context, START_AT + 10).await;
+    let nft_mint = create_programmable_nft(context, &staker.pubkey(), collection).await;
+    let ix = instruction::deposit_programmable(program_id, &staker.pubkey(), pool_pda, &nft_mint, 2, 0, None);
+    let result = process(context, &[ix], &[staker]).await;
+    assert_contract_error(result, ContractError::UnsupportedTokenStandard);
+}
@@ -0,0 +1,110 @@
+use proptest::prelude::*;
+use solana_program::pubkey::Pubkey;
+use staking_nft_2::{
+    common::{
+        get_payroll_reward, get_payroll_reward_due, recalculate_reward_rate, update_payroll_reward,
+        DECIMAL_REWARD, MAX_REWARD_DECIMALS, POOL_PAYROLL_ACCOUNT_TYPE,
+    },
+    schemas::states::payroll::Payroll,
+};
+
+fn payroll(total_deposited_power: u64, total_reward_amount: u64) -> Payroll {
+    let mut payroll = Payroll {
+        account_type: POOL_PAYROLL_ACCOUNT_TYPE,
+        total_deposited_power: 0,
+        reward_withdrawn_amount: 0,
+        total_reward_amount: 0,
+        rate_reward: 0,
+        reward_remainder: 0,
+        claimed_power: 0,
+        sealed: true,
+        index: 2,
+        claimable_after: 0,
+        start_at: 0,
+        pool_pda_account: Pubkey::default(),
+        creator: Pubkey::default(),
+    };
+    update_payroll_reward(&mut payroll, total_deposited_power, total_reward_amount, DECIMAL_REWARD).unwrap();
+    payroll
+}
+
+/// Claims every stake once in order and returns what the payroll paid out.
+fn claim_all(payroll: &mut Payroll, powers: &[u64]) -> u128 {
+    let mut paid = 0;
+    for power in powers {
+        let reward = get_payroll_reward_due(*power, 0, payroll, DECIMAL_REWARD).unwrap();
+        payroll.reward_withdrawn_amount += reward;
+        paid += reward as u128;
+    }
+    paid
+}
+
+proptest! {
+    #[test]
+    fn reward_rate_never_overflows(
+        total_deposited_power: u64,
+        total_reward_amount: u64,
+        reward_decimals in 0..=MAX_REWARD_DECIMALS,
+    ) {
+        let rate_reward =
+            recalculate_reward_rate(total_deposited_power, total_reward_amount, reward_decimals);
+        prop_assert!(rate_reward.is_ok());
+        prop_assert!(get_payroll_reward(total_deposited_power, rate_reward.unwrap(), reward_decimals).is_ok());
+    }
+
+    #[test]
+    fn more_decimals_never_leave_a_larger_remainder(
+        total_deposited_power in 1..=u64::MAX,
+        total_reward_amount: u64,
+        reward_decimals in DECIMAL_REWARD..MAX_REWARD_DECIMALS,
+    ) {
+        let mut payroll = payroll(0, 0);
+        update_payroll_reward(&mut payroll, total_deposited_power, total_reward_amount, reward_decimals).unwrap();
+        let remainder = payroll.reward_remainder;
+        prop_assert!(remainder <= total_reward_amount);
+        update_payroll_reward(&mut payroll, total_deposited_power, total_reward_amount, reward_decimals + 1).unwrap();
+        prop_assert!(payroll.reward_remainder <= remainder);
+    }
+
+    #[test]
+    fn claims_never_exceed_payroll_reward(
+        powers in prop::collection::vec(1..=u64::MAX / 64, 1..32),
+        total_reward_amount: u64,
+    ) {
+        let total_deposited_power = powers.iter().sum();
+        let rate_reward =
+            recalculate_reward_rate(total_deposited_power, total_reward_amount, DECIMAL_REWARD).unwrap();
+        let claimed: u128 = powers
+            .iter()
+            .map(|power| get_payroll_reward(*power, rate_reward, DECIMAL_REWARD).unwrap() as u128)
+            .sum();
+        prop_assert!(claimed <= total_reward_amount as u128);
+
+        let mut payroll = payroll(total_deposited_power, total_reward_amount);
+        prop_assert!(payroll.reward_remainder as u128 <= total_reward_amount as u128 - claimed);
+        prop_assert_eq!(claim_all(&mut payroll, &powers), claimed);
+    }
+
+    #[test]
+    fn payout_capped_by_payroll_reward(
+        powers in prop::collection::vec(any::<u64>(), 1..32),
+        total_deposited_power in 1..=u64::MAX,
+        total_reward_amount: u64,
+    ) {
+        // stakes whose power is out of step with the payroll snapshot still
+        // cannot drain more than the payroll holds
+        let mut payroll = payroll(total_deposited_power, total_reward_amount);
+        let paid = powers
+            .iter()
+            .map(|power| match get_payroll_reward_due(*power, 0, &payroll, DECIMAL_REWARD) {
+                Ok(reward) => {
+                    payroll.reward_withdrawn_amount += reward;
+                    reward as u128
+                }
+                Err(_) => 0,
+            })
+            .sum::<u128>();
+        prop_assert!(paid <= total_reward_amount as u128);
+        prop_assert!(payroll.reward_withdrawn_amount <= payroll.total_reward_amount);
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::*;
+use solana_program_test::{tokio, BanksClientError};
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use staking_nft_2::{
+    common::get_payroll_pda,
+    error::ContractError,
+    instruction,
+    schemas::{instructions::pool_update::PoolUpdateIns, states::{payroll::Payroll, pool::Pool}},
+};
+
+async fn set_reward_decimals(fixture: &mut Fixture, reward_decimals: u8) -> Result<(), BanksClientError> {
+    let ix = instruction::update_pool(
+        &fixture.program_id,
+        &fixture.creator.pubkey(),
+        &fixture.pool_pda,
+        PoolUpdateIns {
+            reward_decimals: Some(reward_decimals),
+            ..PoolUpdateIns::default()
+        },
+    );
+    process(&mut fixture.context, &[ix], &[&fixture.creator]).await
+}
+
+#[tokio::test]
+async fn test_rollover_remainder_once_fully_claimed() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    set_reward_decimals(&mut fixture, 12).await.unwrap();
+    let pool: Pool = get_state(&mut fixture.context, &fixture.pool_pda).await;
+    assert_eq!(pool.reward_decimals, 12);
+    let mut nft_mints = vec![];
+    for _ in 0..3 {
+        nft_mints.push(stake_nft(&mut fixture).await);
+    }
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 1_000, &spl_token::id());
+    process(context, &[ix], &[creator]).await.unwrap();
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.rate_reward, 333_333_333_333_333);
+    assert_eq!(payroll.reward_remainder, 1);
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let rollover_ix = || {
+        instruction::rollover_payroll(program_id, &creator.pubkey(), pool_pda, reward_mint, 2, 4, &spl_token::id())
+    };
+    for (claimed, nft_mint) in nft_mints.iter().enumerate() {
+        // stakes that have not claimed yet are still owed their share
+        let result = process(context, &[rollover_ix()], &[creator]).await;
+        assert_contract_error(result, ContractError::NoRolloverReward);
+        let ix = instruction::withdraw_reward(
+            program_id,
+            &staker.pubkey(),
+            pool_pda,
+            nft_mint,
+            &staker.pubkey(),
+            reward_mint,
+            2,
+            &spl_token::id(),
+        );
+        process(context, &[ix], &[staker]).await.unwrap();
+        let payroll: Payroll = get_state(context, &payroll_pda).await;
+        assert_eq!(payroll.claimed_power, claimed as u64 + 1);
+    }
+    let staker_reward_ata = get_associated_token_address(&staker.pubkey(), reward_mint);
+    assert_eq!(token_balance(context, &staker_reward_ata).await, 999);
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.reward_remainder, 1);
+
+    process(context, &[rollover_ix()], &[creator]).await.unwrap();
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_reward_amount, 999);
+    assert_eq!(payroll.reward_remainder, 0);
+    let (to_payroll_pda, _) = get_payroll_pda(4, pool_pda, program_id);
+    let to_payroll: Payroll = get_state(context, &to_payroll_pda).await;
+    assert_eq!(to_payroll.total_reward_amount, 1);
+    assert_eq!(to_payroll.total_deposited_power, 3);
+    assert_eq!(to_payroll.reward_remainder, 1);
+}
+
+#[tokio::test]
+async fn test_reward_decimals_errors() {
+    let mut fixture = setup_pool().await;
+    set_clock(&mut fixture.context, START_AT - 10).await;
+    let result = set_reward_decimals(&mut fixture, 19).await;
+    assert_contract_error(result, ContractError::InvalidRewardDecimals);
+    set_reward_decimals(&mut fixture, 18).await.unwrap();
+    stake_nft(&mut fixture).await;
+    let result = set_reward_decimals(&mut fixture, 12).await;
+    assert_contract_error(result, ContractError::PoolAlreadyStarted);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::*;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account,
+};
+use spl_token_2022::{
+    extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+    state::Mint,
+};
+use staking_nft_2::{
+    common::{get_payroll_pda, get_pool_pda, get_rewarder_pda},
+    error::ContractError,
+    instruction,
+    schemas::{instructions::pool_initialization::PoolInitializationIns, states::payroll::Payroll},
+};
+
+/// 1% of every transfer is withheld by the mint.
+const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
+
+async fn create_fee_mint(context: &mut ProgramTestContext, owner: &Pubkey, amount: u64) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        initialize_transfer_fee_config(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            Some(&payer),
+            Some(&payer),
+            TRANSFER_FEE_BASIS_POINTS,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint(&spl_token_2022::id(), &mint.pubkey(), &payer, None, 6).unwrap(),
+        create_associated_token_account(&payer, owner, &mint.pubkey(), &spl_token_2022::id()),
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &get_associated_token_address_with_program_id(owner, &mint.pubkey(), &spl_token_2022::id()),
+            &payer,
+            &[],
+            amount,
+        )
+        .unwrap(),
+    ];
+    process(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+fn initialize_pool(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    reward_mint: &Pubkey,
+    collection: &Pubkey,
+    reward_token_program: &Pubkey,
+) -> Instruction {
+    instruction::initialize_pool(
+        program_id,
+        creator,
+        reward_mint,
+        PoolInitializationIns {
+            name: pool_name("token2022"),
+            reward_period: REWARD_PERIOD,
+            start_at: START_AT,
+            creator: *creator,
+            collection: *collection,
+            pool_type: 0,
+        },
+        reward_token_program,
+    )
+}
+
+/// Same as `setup_pool` with a Token-2022 reward mint charging transfer fees.
+async fn setup_fee_pool() -> Fixture {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    set_clock(&mut context, START_AT).await;
+    let creator = Keypair::new();
+    let staker = Keypair::new();
+    fund(&mut context, &creator.pubkey(), 10_000_000_000).await;
+    fund(&mut context, &staker.pubkey(), 10_000_000_000).await;
+    let reward_mint = create_fee_mint(&mut context, &creator.pubkey(), 1_000_000_000).await;
+    let collection = Pubkey::new_unique();
+    let ix = initialize_pool(&program_id, &creator.pubkey(), &reward_mint, &collection, &spl_token_2022::id());
+    process(&mut context, &[ix], &[&creator]).await.unwrap();
+    let (pool_pda, _) = get_pool_pda(&pool_name("token2022"), &creator.pubkey(), &program_id);
+    Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        collection,
+        pool_pda,
+    }
+}
+
+#[tokio::test]
+async fn test_reward_with_transfer_fee() {
+    let mut fixture = setup_fee_pool().await;
+    let nft_mint = stake_nft(&mut fixture).await;
+    let Fixture {
+        context,
+        program_id,
+        creator,
+        staker,
+        reward_mint,
+        pool_pda,
+        ..
+    } = &mut fixture;
+    let ix = instruction::add_reward(
+        program_id,
+        &creator.pubkey(),
+        pool_pda,
+        reward_mint,
+        2,
+        1_000_000,
+        &spl_token_2022::id(),
+    );
+    process(context, &[ix], &[creator]).await.unwrap();
+    let (payroll_pda, _) = get_payroll_pda(2, pool_pda, program_id);
+    let (rewarder_pda, _) = get_rewarder_pda(&payroll_pda, pool_pda, program_id);
+    let rewarder_ata = get_associated_token_address_with_program_id(&rewarder_pda, reward_mint, &spl_token_2022::id());
+    // only what reached the rewarder is paid out
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    assert_eq!(payroll.total_reward_amount, 990_000);
+    assert_eq!(token_balance(context, &rewarder_ata).await, 990_000);
+
+    set_clock(context, START_AT + 2 * REWARD_PERIOD).await;
+    seal_payroll(context, program_id, pool_pda, 2).await;
+    let ix = instruction::withdraw_reward(
+        program_id,
+        &staker.pubkey(),
+        pool_pda,
+        &nft_mint,
+        &staker.pubkey(),
+        reward_mint,
+        2,
+        &spl_token_2022::id(),
+    );
+    process(context, &[ix], &[staker]).await.unwrap();
+    let payroll: Payroll = get_state(context, &payroll_pda).await;
+    let staker_ata = get_associated_token_address_with_program_id(&staker.pubkey(), reward_mint, &spl_token_2022::id());
+    // the payroll tracks what left the rewarder, the staker bears the fee
+    assert_eq!(payroll.reward_withdrawn_amount, 990_000);
+    assert_eq!(token_balance(context, &rewarder_ata).await, 0);
+    assert_eq!(token_balance(context, &staker_ata).await, 980_100);
+}
+
+#[tokio::test]
+async fn test_reward_mint_of_other_token_program() {
+    let (program_test, program_id) = program_test();
+    let mut context = program_test.start_with_context().await;
+    let creator = Keypair::new();
+    fund(&mut context, &creator.pubkey(), 10_000_000_000).await;
+    let reward_mint = create_fee_mint(&mut context, &creator.pubkey(), 1_000).await;
+    let ix = initialize_pool(&program_id, &creator.pubkey(), &reward_mint, &Pubkey::new_unique(), &spl_token::id());
+    let result = process(&mut context, &[ix], &[&creator]).await;
+    assert_contract_error(result, ContractError::InvalidRewardToken);
+}